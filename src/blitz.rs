@@ -0,0 +1,408 @@
+//! Experimental blitz variant, where both teams propose a move for the same
+//! turn and proposals are resolved together after a limited number of turns.
+
+use std::{
+  collections::{HashMap, HashSet},
+  fmt::Display,
+  str::FromStr,
+};
+
+use crate::{
+  board::PlacementOutcome,
+  error::{BlitzError, GameError, ParseMoveError},
+  game::{Game, Move, Phase},
+  piece::Piece,
+  position::Position,
+  Team,
+};
+
+/// How proposals covering the same tiles are resolved.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ConflictRule {
+  /// Both proposals are rejected.
+  #[default]
+  RejectBoth,
+  /// The proposal with the larger piece is applied. Pieces of equal size
+  /// are both rejected.
+  LargerPiece,
+}
+
+/// Why a proposal was not applied.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Rejection {
+  /// The proposal covers tiles the other team's proposal covers too.
+  Conflict,
+  /// The proposal is not a legal move, either from the start or after the
+  /// other team's proposal was applied.
+  Illegal(GameError),
+}
+
+/// What happened to a team's proposal.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Verdict {
+  pub mv: Move,
+  pub result: Result<PlacementOutcome, Rejection>,
+}
+
+impl Verdict {
+  /// Returns message announcing the verdict.
+  pub fn message(&self) -> Message {
+    match self.result {
+      Ok(_) => Message::Applied(self.mv),
+      Err(Rejection::Conflict) => Message::Conflict(self.mv),
+      Err(Rejection::Illegal(_)) => Message::Rejected(self.mv),
+    }
+  }
+}
+
+/// Verdicts of a resolved turn.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Round {
+  pub white: Verdict,
+  pub black: Verdict,
+}
+
+/// A game where both teams move at once. Each turn both teams propose a
+/// move, then the proposals are resolved deterministically:
+///
+///   - proposals that are illegal on their own are rejected;
+///   - legal placements covering the same tiles are resolved by the
+///     conflict rule;
+///   - remaining proposals are applied one by one, larger pieces first and
+///     white team first among equals. A proposal that the first one made
+///     illegal is rejected.
+pub struct Blitz {
+  game: Game,
+  conflict_rule: ConflictRule,
+  turns_left: usize,
+  proposals: HashMap<Team, Move>,
+}
+
+impl Blitz {
+  /// Returns blitz on given `game` that lasts at most `turns` turns. Panics
+  /// if the cathedral is not placed yet, since only white team places it,
+  /// or if the game is not between white and black teams.
+  pub fn new(game: Game, conflict_rule: ConflictRule, turns: usize) -> Self {
+    assert_eq!(game.phase(), Phase::Pieces, "cathedral must be placed");
+    assert_eq!(
+      game.players(),
+      [Team::White, Team::Black],
+      "blitz is played by two teams"
+    );
+    Self {
+      game,
+      conflict_rule,
+      turns_left: turns,
+      proposals: HashMap::new(),
+    }
+  }
+
+  pub fn game(&self) -> &Game {
+    &self.game
+  }
+
+  pub fn turns_left(&self) -> usize {
+    self.turns_left
+  }
+
+  /// Returns `true` if the turn limit is reached or the game is over.
+  pub fn is_over(&self) -> bool {
+    self.turns_left == 0 || self.game.is_over()
+  }
+
+  /// Returns `true` if `team` has proposed its move for the current turn.
+  pub fn has_proposed(&self, team: Team) -> bool {
+    self.proposals.contains_key(&team)
+  }
+
+  /// Records move proposed by its team for the current turn. Once both
+  /// teams have proposed, the turn is resolved and its verdicts returned.
+  pub fn propose(&mut self, mv: Move) -> Result<Option<Round>, BlitzError> {
+    let team = mv.team();
+    if !matches!(team, Team::White | Team::Black) {
+      return Err(BlitzError::InvalidTeam);
+    }
+    if self.is_over() {
+      return Err(BlitzError::Over);
+    }
+    if self.has_proposed(team) {
+      return Err(BlitzError::AlreadyProposed(team));
+    }
+    self.proposals.insert(team, mv);
+    let (Some(&white), Some(&black)) = (
+      self.proposals.get(&Team::White),
+      self.proposals.get(&Team::Black),
+    ) else {
+      return Ok(None);
+    };
+    self.proposals.clear();
+    self.turns_left -= 1;
+    Ok(Some(self.resolve(white, black)))
+  }
+
+  fn resolve(&mut self, white: Move, black: Move) -> Round {
+    let mut white = Verdict {
+      mv: white,
+      result: self.check(white).map(|()| PlacementOutcome::default()),
+    };
+    let mut black = Verdict {
+      mv: black,
+      result: self.check(black).map(|()| PlacementOutcome::default()),
+    };
+
+    if white.result.is_ok() && black.result.is_ok() {
+      let white_tiles = footprint(white.mv);
+      if !white_tiles.is_disjoint(&footprint(black.mv)) {
+        let (w, b) = (size(white.mv), size(black.mv));
+        match self.conflict_rule {
+          ConflictRule::LargerPiece if w > b => {
+            black.result = Err(Rejection::Conflict)
+          }
+          ConflictRule::LargerPiece if b > w => {
+            white.result = Err(Rejection::Conflict)
+          }
+          _ => {
+            white.result = Err(Rejection::Conflict);
+            black.result = Err(Rejection::Conflict);
+          }
+        }
+      }
+    }
+
+    let mut order = [&mut white, &mut black];
+    if size(order[1].mv) > size(order[0].mv) {
+      order.reverse();
+    }
+    for verdict in order {
+      if verdict.result.is_ok() {
+        verdict.result = self
+          .game
+          .play_out_of_turn(verdict.mv)
+          .map_err(Rejection::Illegal);
+      }
+    }
+    Round { white, black }
+  }
+
+  /// Returns error the move would fail with if played now.
+  fn check(&mut self, mv: Move) -> Result<(), Rejection> {
+    self.game.check_out_of_turn(mv).map_err(Rejection::Illegal)
+  }
+}
+
+/// Returns tiles the move would cover.
+fn footprint(mv: Move) -> HashSet<Position> {
+  match mv {
+    Move::Place {
+      team,
+      kind,
+      rotation,
+      position,
+    } => {
+      let mut piece = Piece::new(kind, team);
+      piece.rotate_to(rotation);
+      piece.occupied_positions_iter(position).collect()
+    }
+    _ => HashSet::new(),
+  }
+}
+
+/// Returns number of squares of the piece the move places.
+fn size(mv: Move) -> usize {
+  match mv {
+    Move::Place { kind, .. } => kind.square_count(),
+    _ => 0,
+  }
+}
+
+/// Message of the blitz protocol, written as a keyword followed by a move in
+/// its notation, e.g. `PROPOSE W:TAVERN@a1^U`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Message {
+  /// A team proposes its move for the current turn.
+  Propose(Move),
+  /// The proposal was applied.
+  Applied(Move),
+  /// The proposal was rejected because another proposal covered the same
+  /// tiles.
+  Conflict(Move),
+  /// The proposal was rejected as an illegal move.
+  Rejected(Move),
+}
+
+impl Display for Message {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Propose(mv) => write!(f, "PROPOSE {mv}"),
+      Self::Applied(mv) => write!(f, "APPLIED {mv}"),
+      Self::Conflict(mv) => write!(f, "CONFLICT {mv}"),
+      Self::Rejected(mv) => write!(f, "REJECTED {mv}"),
+    }
+  }
+}
+
+impl FromStr for Message {
+  type Err = ParseMoveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (keyword, mv) =
+      s.split_once(' ').ok_or(ParseMoveError::InvalidFormat)?;
+    let mv = mv.parse()?;
+    match keyword {
+      "PROPOSE" => Ok(Self::Propose(mv)),
+      "APPLIED" => Ok(Self::Applied(mv)),
+      "CONFLICT" => Ok(Self::Conflict(mv)),
+      "REJECTED" => Ok(Self::Rejected(mv)),
+      _ => Err(ParseMoveError::InvalidFormat),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    consts::PIECES_PER_TEAM,
+    error::BoardError,
+    piece::{PieceKind, Rotation},
+  };
+
+  fn place(team: Team, kind: PieceKind, position: (usize, usize)) -> Move {
+    Move::Place {
+      team,
+      kind,
+      rotation: Rotation::UP,
+      position: position.into(),
+    }
+  }
+
+  fn started_blitz(conflict_rule: ConflictRule, turns: usize) -> Blitz {
+    let mut game = Game::new();
+    game
+      .play(place(Team::White, PieceKind::Cathedral, (3, 3)))
+      .expect("cathedral must fit");
+    Blitz::new(game, conflict_rule, turns)
+  }
+
+  #[test]
+  fn test_blitz_turn() -> Result<(), BlitzError> {
+    let mut blitz = started_blitz(ConflictRule::RejectBoth, 2);
+    let white = place(Team::White, PieceKind::Tavern, (0, 0));
+    let black = place(Team::Black, PieceKind::Castle, (8, 0));
+    assert_eq!(blitz.propose(white)?, None);
+    assert!(blitz.has_proposed(Team::White));
+    assert_eq!(
+      blitz.propose(white),
+      Err(BlitzError::AlreadyProposed(Team::White))
+    );
+    assert_eq!(
+      blitz.propose(Move::Pass { team: Team::None }),
+      Err(BlitzError::InvalidTeam)
+    );
+
+    let round = blitz.propose(black)?.expect("both teams proposed");
+    assert_eq!([round.white.message(), round.black.message()], [
+      Message::Applied(white),
+      Message::Applied(black)
+    ]);
+    assert_eq!(blitz.turns_left(), 1);
+    assert!(!blitz.has_proposed(Team::White));
+    for team in [Team::White, Team::Black] {
+      let hand = blitz.game().hand(team);
+      assert_eq!(hand.remaining().len(), PIECES_PER_TEAM - 1);
+    }
+
+    // Illegal proposals don't affect the other team's one.
+    let white = place(Team::White, PieceKind::Tavern, (0, 9));
+    let black = place(Team::Black, PieceKind::Castle, (0, 0));
+    blitz.propose(black)?;
+    let round = blitz.propose(white)?.expect("both teams proposed");
+    assert!(round
+      .white
+      .result
+      .as_ref()
+      .is_ok_and(|outcome| outcome.captured_pieces.is_empty()));
+    assert_eq!(
+      round.black.result,
+      Err(Rejection::Illegal(GameError::PieceNotInHand(
+        PieceKind::Castle
+      )))
+    );
+    assert!(blitz.is_over());
+    assert_eq!(blitz.propose(white), Err(BlitzError::Over));
+    Ok(())
+  }
+
+  #[test]
+  fn test_blitz_conflicts() -> Result<(), BlitzError> {
+    let white = place(Team::White, PieceKind::Castle, (0, 0));
+    let black = place(Team::Black, PieceKind::Tavern, (1, 2));
+
+    let mut blitz = started_blitz(ConflictRule::RejectBoth, 1);
+    blitz.propose(white)?;
+    let round = blitz.propose(black)?.expect("both teams proposed");
+    assert_eq!(round.white.result, Err(Rejection::Conflict));
+    assert_eq!(round.black.message(), Message::Conflict(black));
+    assert_eq!(blitz.game().board().pieces().count(), 1);
+    // Checking proposals leaves nothing to redo.
+    assert_eq!(blitz.game.redo(), None);
+
+    let mut blitz = started_blitz(ConflictRule::LargerPiece, 1);
+    blitz.propose(black)?;
+    let round = blitz.propose(white)?.expect("both teams proposed");
+    assert_eq!(round.white.message(), Message::Applied(white));
+    assert_eq!(round.black.result, Err(Rejection::Conflict));
+
+    // Pieces of equal size are both rejected.
+    let mut blitz = started_blitz(ConflictRule::LargerPiece, 1);
+    blitz.propose(place(Team::White, PieceKind::Tavern, (0, 0)))?;
+    let round = blitz
+      .propose(place(Team::Black, PieceKind::Tavern, (0, 0)))?
+      .expect("both teams proposed");
+    assert_eq!(round.white.result, Err(Rejection::Conflict));
+    assert_eq!(round.black.result, Err(Rejection::Conflict));
+    Ok(())
+  }
+
+  #[test]
+  fn test_blitz_order() -> Result<(), BlitzError> {
+    // White's larger piece goes first and walls the corner off, so black
+    // can't place there anymore.
+    let mut blitz = started_blitz(ConflictRule::RejectBoth, 1);
+    let inn = Move::Place {
+      team: Team::White,
+      kind: PieceKind::Inn,
+      rotation: Rotation::DOWN,
+      position: (0, 0).into(),
+    };
+    blitz.propose(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    let round = blitz.propose(inn)?.expect("both teams proposed");
+    assert_eq!(round.white.message(), Message::Applied(inn));
+    assert_eq!(
+      round.black.result,
+      Err(Rejection::Illegal(GameError::Board(
+        BoardError::PieceOnEnemyTile((0, 0).into())
+      )))
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_message() -> Result<(), ParseMoveError> {
+    let mv = place(Team::Black, PieceKind::Academy, (2, 3));
+    for message in [
+      Message::Propose(mv),
+      Message::Applied(mv),
+      Message::Conflict(mv),
+      Message::Rejected(mv),
+    ] {
+      assert_eq!(message.to_string().parse::<Message>()?, message);
+    }
+    assert_eq!(Message::Propose(mv).to_string(), "PROPOSE B:ACADEMY@d3^U");
+    assert_eq!(
+      "ACCEPT B:PASS".parse::<Message>(),
+      Err(ParseMoveError::InvalidFormat)
+    );
+    Ok(())
+  }
+}
@@ -1,14 +1,26 @@
 use std::{
   collections::{HashMap, HashSet},
   fmt::Display,
+  mem,
+  ops::Index,
 };
 
 use ndarray::{Array, Array2};
 
+mod csv;
+mod editor;
+
+pub use editor::BoardEditor;
+
 use crate::{
-  error::BoardError,
-  piece::{Piece, Placed, Released},
+  consts::{MAX_PIECE_DIMENSION, STANDARD_BOARD_SIZE},
+  error::{BoardError, InvalidBoardError},
+  game::Move,
+  layout::Layout,
+  piece::{Piece, PieceKind, Placed, Released, Rotation},
   position::Position,
+  rng::SplitMix64,
+  rules::{Connectivity, PieceSetPreset, Rules, Scoring},
   Team,
 };
 
@@ -16,6 +28,9 @@ use crate::{
 pub enum Tile {
   Empty(Team),
   Occupied(Team),
+  /// Neutral tile nobody can place on or claim. Blocked tiles act like walls
+  /// for both teams.
+  Blocked,
 }
 
 impl Display for Tile {
@@ -23,28 +38,379 @@ impl Display for Tile {
     match self {
       Tile::Empty(team) if *team == Team::None => write!(f, "  "),
       Tile::Empty(team) | Tile::Occupied(team) => write!(f, "{team}"),
+      Tile::Blocked => write!(f, "▒▒"),
+    }
+  }
+}
+
+/// One of eight ways to map a square board onto itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Symmetry {
+  Identity,
+  /// Quarter turn clockwise.
+  Rotate90,
+  Rotate180,
+  /// Quarter turn counterclockwise.
+  Rotate270,
+  /// Flip rows upside down.
+  FlipRows,
+  /// Flip columns left to right.
+  FlipColumns,
+  /// Reflection across the main diagonal.
+  Transpose,
+  /// Reflection across the anti-diagonal.
+  AntiTranspose,
+}
+
+impl Symmetry {
+  pub const ALL: [Self; 8] = [
+    Self::Identity,
+    Self::Rotate90,
+    Self::Rotate180,
+    Self::Rotate270,
+    Self::FlipRows,
+    Self::FlipColumns,
+    Self::Transpose,
+    Self::AntiTranspose,
+  ];
+
+  /// Returns where `position` ends up on a square board of `size` tiles
+  /// per side.
+  pub fn apply(self, position: Position, size: usize) -> Position {
+    let Position { x, y } = position;
+    let last = size - 1;
+    let (x, y) = match self {
+      Self::Identity => (x, y),
+      Self::Rotate90 => (y, last - x),
+      Self::Rotate180 => (last - x, last - y),
+      Self::Rotate270 => (last - y, x),
+      Self::FlipRows => (last - x, y),
+      Self::FlipColumns => (x, last - y),
+      Self::Transpose => (y, x),
+      Self::AntiTranspose => (last - y, last - x),
+    };
+    Position { x, y }
+  }
+}
+
+/// Where the cathedral currently is.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CathedralStatus {
+  /// The cathedral has not been placed yet.
+  InBox,
+  /// The cathedral stands on the board with its layout at given position.
+  Placed(Position),
+  /// The cathedral was captured and is out of play for good.
+  Captured,
+}
+
+/// Handle of a piece on a board. A board gives ids out in order pieces are
+/// placed, and a piece keeps its id while it stays on the board, even if
+/// moved. Undoing a placement takes its id back, so redoing it gives the
+/// piece the same id.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PieceId(usize);
+
+/// What placing a piece on the board resulted in.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct PlacementOutcome {
+  /// Id of the placed piece. Outcomes of moves that place nothing have none.
+  pub placed_id: Option<PieceId>,
+  /// Pieces captured by the placement, in order they were placed.
+  pub captured_pieces: Vec<Piece<Released>>,
+  /// Ids captured pieces had, in the same order.
+  pub captured_ids: Vec<PieceId>,
+  /// Empty tiles that became claimed by the placing team, including tiles
+  /// freed from captured pieces.
+  pub claimed_tiles: HashSet<Position>,
+  /// Whether one of captured pieces is the cathedral.
+  pub cathedral_captured: bool,
+}
+
+/// What a placement changed on the board, enough to take it back.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct PlacementUndo {
+  /// Captured pieces with their indices in placement order at the moment
+  /// they were removed.
+  captured: Vec<(usize, PieceId, Piece<Placed>)>,
+  /// Tiles the placement changed with their previous values.
+  tiles: Vec<(Position, Tile)>,
+  cathedral_captured: bool,
+  /// Id the board would have given out next before the placement.
+  next_id: usize,
+}
+
+/// Live standing of a team.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Score {
+  /// Squares of the team's pieces that are not on the board. The fewer, the
+  /// better.
+  pub unplaced_squares: usize,
+  /// Empty tiles the team has claimed.
+  pub territory: usize,
+  /// Pieces the team has captured so far, the cathedral included.
+  pub captured_pieces: usize,
+}
+
+impl Score {
+  /// Returns penalty of this score under given `scoring`. The lower, the
+  /// better.
+  pub fn penalty(&self, scoring: Scoring) -> isize {
+    let unplaced = self.unplaced_squares as isize;
+    match scoring {
+      Scoring::Unplaced => unplaced,
+      Scoring::WeightedTerritory { weight } => {
+        unplaced - (self.territory * weight) as isize
+      }
+    }
+  }
+}
+
+/// Tiles a team has on a single row or column of the board.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct TeamTiles {
+  /// Tiles under the team's pieces.
+  pub occupied: usize,
+  /// Empty tiles the team has claimed.
+  pub claimed: usize,
+}
+
+/// Tiles each team has on a single row or column of the board.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct LineCounts {
+  pub white: TeamTiles,
+  pub black: TeamTiles,
+  pub red: TeamTiles,
+  pub green: TeamTiles,
+}
+
+impl LineCounts {
+  /// Returns tiles of given `team`. `Team::None` has none counted.
+  pub fn team(&self, team: Team) -> TeamTiles {
+    match team {
+      Team::White => self.white,
+      Team::Black => self.black,
+      Team::Red => self.red,
+      Team::Green => self.green,
+      Team::None => TeamTiles::default(),
+    }
+  }
+
+  fn add(&mut self, tile: Tile) {
+    let (team, occupied) = match tile {
+      Tile::Occupied(team) => (team, true),
+      Tile::Empty(team) => (team, false),
+      Tile::Blocked => return,
+    };
+    let tiles = match team {
+      Team::White => &mut self.white,
+      Team::Black => &mut self.black,
+      Team::Red => &mut self.red,
+      Team::Green => &mut self.green,
+      Team::None => return,
+    };
+    if occupied {
+      tiles.occupied += 1;
+    } else {
+      tiles.claimed += 1;
     }
   }
 }
 
 pub struct Board {
   tiles: Array2<Tile>,
-  pieces: HashMap<Position, Piece<Placed>>,
+  pieces: HashMap<PieceId, Piece<Placed>>,
+  /// Ids of `pieces` in order the pieces were placed.
+  placement_order: Vec<PieceId>,
+  /// Id the next placed piece gets.
+  next_id: usize,
+  cathedral_captured: bool,
+  /// Kinds of pieces each team has captured, in order of capture.
+  captures: HashMap<Team, Vec<PieceKind>>,
+  rules: Rules,
 }
 
 impl Board {
   pub fn with_size(size: usize) -> Self {
+    Self::with_size_and_rules(size, Rules::default())
+  }
+
+  pub fn with_size_and_rules(size: usize, rules: Rules) -> Self {
     Self {
       tiles: Array::from_elem((size, size), Tile::Empty(Team::None)),
       pieces: HashMap::new(),
+      placement_order: Vec::new(),
+      next_id: 0,
+      cathedral_captured: false,
+      captures: HashMap::new(),
+      rules,
+    }
+  }
+
+  /// Removes all pieces and blocked tiles, turning the board into a freshly
+  /// created one of the same size and rules. Keeps allocated memory for
+  /// reuse.
+  pub fn reset(&mut self) {
+    self.reset_with(self.rules);
+  }
+
+  /// Same as `reset` but returns pieces that were on the board in
+  /// `Released` state, from the earliest placed one to the latest.
+  pub fn clear(&mut self) -> Vec<Piece<Released>> {
+    let pieces = self
+      .placement_order
+      .iter()
+      .filter_map(|id| self.pieces.remove(id))
+      .map(Piece::released)
+      .collect();
+    self.reset();
+    pieces
+  }
+
+  /// Same as `reset` but also replaces board's rules with given `rules`.
+  pub fn reset_with(&mut self, rules: Rules) {
+    self.tiles.fill(Tile::Empty(Team::None));
+    self.pieces.clear();
+    self.placement_order.clear();
+    self.next_id = 0;
+    self.cathedral_captured = false;
+    self.captures.clear();
+    self.rules = rules;
+  }
+
+  /// Blocks `count` random empty neutral tiles, the "ruins" of the chaos
+  /// variant. Tiles are picked by a pseudo-random generator with given `seed`,
+  /// so the same seed on the same board always blocks the same tiles. If
+  /// there are fewer empty tiles than `count`, all of them get blocked.
+  pub fn scatter_ruins(&mut self, seed: u64, count: usize) {
+    let mut candidates = self
+      .tiles
+      .indexed_iter()
+      .filter(|(_, tile)| **tile == Tile::Empty(Team::None))
+      .map(|(p, _)| p)
+      .collect::<Vec<_>>();
+    let mut rng = SplitMix64::new(seed);
+    for _ in 0..count.min(candidates.len()) {
+      let p = candidates.swap_remove(rng.below(candidates.len()));
+      self.tiles[p] = Tile::Blocked;
     }
   }
 
+  /// Returns rules this board plays by.
+  pub fn rules(&self) -> Rules {
+    self.rules
+  }
+
   /// Returns board's size as position.
   pub fn size(&self) -> Position {
     Position::from(self.tiles.dim())
   }
 
+  /// Returns number of rows of tiles.
+  pub fn rows(&self) -> usize {
+    self.tiles.nrows()
+  }
+
+  /// Returns number of columns of tiles.
+  pub fn cols(&self) -> usize {
+    self.tiles.ncols()
+  }
+
+  /// Returns iterator of all tiles with their positions, row by row.
+  pub fn tiles_iter(&self) -> impl Iterator<Item = (Position, Tile)> + '_ {
+    self
+      .tiles
+      .indexed_iter()
+      .map(|(index, tile)| (Position::from(index), *tile))
+  }
+
+  /// Returns approximate number of bytes the board takes, including its heap
+  /// allocations: tiles, stored pieces, placement order and captures.
+  pub fn memory_footprint(&self) -> usize {
+    let tiles = self.tiles.len() * mem::size_of::<Tile>();
+    let pieces =
+      self.pieces.capacity() * mem::size_of::<(PieceId, Piece<Placed>)>();
+    let placement_order =
+      self.placement_order.capacity() * mem::size_of::<PieceId>();
+    let captures = self.captures.capacity()
+      * mem::size_of::<(Team, Vec<PieceKind>)>()
+      + self
+        .captures
+        .values()
+        .map(|kinds| kinds.capacity() * mem::size_of::<PieceKind>())
+        .sum::<usize>();
+    mem::size_of::<Self>() + tiles + pieces + placement_order + captures
+  }
+
+  /// Returns tile at given `position` or `None` if it is out of bounds.
+  pub fn get(&self, position: Position) -> Option<Tile> {
+    self.tiles.get((position.x, position.y)).copied()
+  }
+
+  /// Returns tile at given `position`. Panics if it is out of bounds.
+  pub fn tile(&self, position: Position) -> Tile {
+    self.tiles[(position.x, position.y)]
+  }
+
+  /// Returns piece covering tile at given `position`, if any.
+  pub fn piece_at(&self, position: Position) -> Option<&Piece<Placed>> {
+    self.piece_id_at(position).map(|id| &self.pieces[&id])
+  }
+
+  /// Returns piece with given `id`, if it is on the board.
+  pub fn piece_by_id(&self, id: PieceId) -> Option<&Piece<Placed>> {
+    self.pieces.get(&id)
+  }
+
+  /// Returns id of piece covering tile at given `position`, if any.
+  pub fn piece_id_at(&self, position: Position) -> Option<PieceId> {
+    if !matches!(
+      self.tiles.get((position.x, position.y)),
+      Some(Tile::Occupied(_))
+    ) {
+      return None;
+    }
+    self
+      .pieces
+      .iter()
+      .find(|(_, piece)| piece.occupied_positions_iter().any(|p| p == position))
+      .map(|(id, _)| *id)
+  }
+
+  /// Returns iterator of pieces placed on the board in arbitrary order.
+  pub(crate) fn pieces(&self) -> impl Iterator<Item = &Piece<Placed>> {
+    self.pieces.values()
+  }
+
+  /// Returns iterator of pieces on the board with their ids, from the
+  /// earliest placed one to the latest.
+  pub fn pieces_iter(
+    &self,
+  ) -> impl Iterator<Item = (PieceId, &Piece<Placed>)> + '_ {
+    self
+      .placement_order
+      .iter()
+      .map(|id| (*id, &self.pieces[id]))
+  }
+
+  /// Same as `pieces_iter` but only yields pieces of given `team`.
+  pub fn team_pieces_iter(
+    &self,
+    team: Team,
+  ) -> impl Iterator<Item = (PieceId, &Piece<Placed>)> + '_ {
+    self
+      .pieces_iter()
+      .filter(move |(_, piece)| piece.team() == team)
+  }
+
+  /// Returns iterator of pieces on the board, from the earliest placed one to
+  /// the latest.
+  pub fn pieces_in_placement_order(
+    &self,
+  ) -> impl Iterator<Item = &Piece<Placed>> {
+    self.placement_order.iter().map(|p| &self.pieces[p])
+  }
+
   /// Checks if piece can be placed on board at given position. Returns possible
   /// error that can occur during placement.
   pub fn can_place_piece(
@@ -52,44 +418,342 @@ impl Board {
     piece: &Piece<Released>,
     position: Position,
   ) -> Result<(), BoardError> {
+    if piece.kind() == PieceKind::Cathedral && self.cathedral_captured {
+      return Err(BoardError::CathedralOutOfPlay);
+    }
     for p in piece.occupied_positions_iter(position) {
       let tile = self
         .tiles
         .get((p.x, p.y))
         .ok_or(BoardError::PieceOutOfBounds(p))?;
       match tile {
-        Tile::Empty(team) if piece.team().is_opposing_team(team) => {
+        Tile::Empty(team)
+          if self.rules.claimed_ground_blocks
+            && piece.team().is_opposing_team(team) =>
+        {
           return Err(BoardError::PieceOnEnemyTile(p))
         }
         Tile::Occupied(_) => return Err(BoardError::PieceOnOccupiedTile(p)),
+        Tile::Blocked => return Err(BoardError::PieceOnBlockedTile(p)),
         _ => (),
       }
     }
     Ok(())
   }
 
-  /// Tries to put piece on board at given position.
+  /// Cheap legality pre-check. Returns `true` if piece certainly can't be
+  /// placed at given position, because it sticks out of board's bounds or
+  /// covers an occupied or blocked tile. Returning `false` does not guarantee
+  /// that `can_place_piece` succeeds since tiles of other teams are not
+  /// checked.
+  pub fn quick_reject(
+    &self,
+    piece: &Piece<Released>,
+    position: Position,
+  ) -> bool {
+    let size = self.size();
+    match position.checked_add(piece.size()) {
+      Some(p) if p.x <= size.x && p.y <= size.y => (),
+      _ => return true,
+    }
+    piece.occupied_positions_iter(position).any(|p| {
+      matches!(self.tiles[(p.x, p.y)], Tile::Occupied(_) | Tile::Blocked)
+    })
+  }
+
+  /// Returns every placement of `piece` that is currently legal. A placement
+  /// is an orientation of the piece and position of its layout; orientations
+  /// producing the same layout are listed once.
+  pub fn legal_placements(
+    &self,
+    piece: &Piece<Released>,
+  ) -> Vec<(Rotation, Position)> {
+    let size = self.size();
+    let mut placements = Vec::new();
+    for rotation in piece.distinct_orientations() {
+      let mut piece = piece.clone();
+      piece.rotate_to(rotation);
+      let piece_size = piece.size();
+      if piece_size.x > size.x || piece_size.y > size.y {
+        continue;
+      }
+      for x in 0..=size.x - piece_size.x {
+        for y in 0..=size.y - piece_size.y {
+          let position = Position { x, y };
+          if !self.quick_reject(&piece, position)
+            && self.can_place_piece(&piece, position).is_ok()
+          {
+            placements.push((rotation, position));
+          }
+        }
+      }
+    }
+    placements
+  }
+
+  /// Returns every placement of `piece` that is currently legal and covers
+  /// only tiles in given `region`. A placement is an orientation of the piece
+  /// and position of its layout; orientations producing the same layout are
+  /// listed once.
+  pub fn legal_placements_in(
+    &self,
+    piece: &Piece<Released>,
+    region: &HashSet<Position>,
+  ) -> Vec<(Rotation, Position)> {
+    let (Some(max_x), Some(max_y)) = (
+      region.iter().map(|p| p.x).max(),
+      region.iter().map(|p| p.y).max(),
+    ) else {
+      return Vec::new();
+    };
+    let min_x = region.iter().map(|p| p.x).min().unwrap_or_default();
+    let min_y = region.iter().map(|p| p.y).min().unwrap_or_default();
+
+    let mut placements = Vec::new();
+    for rotation in piece.distinct_orientations() {
+      let mut piece = piece.clone();
+      piece.rotate_to(rotation);
+      let size = piece.size();
+      for x in min_x.saturating_sub(size.x - 1)..=max_x {
+        for y in min_y.saturating_sub(size.y - 1)..=max_y {
+          let position = Position { x, y };
+          if !self.quick_reject(&piece, position)
+            && piece
+              .occupied_positions_iter(position)
+              .all(|p| region.contains(&p))
+            && self.can_place_piece(&piece, position).is_ok()
+          {
+            placements.push((rotation, position));
+          }
+        }
+      }
+    }
+    placements
+  }
+
+  /// Tries to put piece on board at given position. If the piece encloses
+  /// regions of the board holding at most one enemy piece or the cathedral,
+  /// that piece is captured and empty tiles are claimed by piece's team, so
+  /// the other team can't place there. Regions holding two or more such
+  /// pieces are left untouched. A captured cathedral is out of play for the
+  /// rest of the game. The cathedral itself never captures anything. Board's
+  /// rules may rule out capturing occupied regions or the cathedral.
+  /// Returns what the placement resulted in.
   pub fn try_place_piece(
     &mut self,
     piece: Piece<Released>,
     position: Position,
-  ) -> Result<Vec<Piece<Released>>, BoardError> {
+  ) -> Result<PlacementOutcome, BoardError> {
+    self
+      .try_place_piece_undoable(piece, position)
+      .map(|(outcome, _)| outcome)
+  }
+
+  /// Same as `try_place_piece`, but also returns what the placement changed,
+  /// so `undo_placement` can take it back.
+  pub(crate) fn try_place_piece_undoable(
+    &mut self,
+    piece: Piece<Released>,
+    position: Position,
+  ) -> Result<(PlacementOutcome, PlacementUndo), BoardError> {
+    let id = PieceId(self.next_id);
+    let placement = self.try_place_piece_as(piece, position, id)?;
+    self.next_id += 1;
+    Ok(placement)
+  }
+
+  /// Places piece like `try_place_piece_undoable` does, storing it with
+  /// given `id`.
+  fn try_place_piece_as(
+    &mut self,
+    piece: Piece<Released>,
+    position: Position,
+    id: PieceId,
+  ) -> Result<(PlacementOutcome, PlacementUndo), BoardError> {
     self.can_place_piece(&piece, position)?;
     let piece = piece.placed_at(position);
+    let team = piece.team();
+    let (captured_ids, claimed_tiles) = self.resolve_placement(&piece);
+    let mut undo = PlacementUndo {
+      captured: Vec::new(),
+      tiles: piece
+        .occupied_positions_iter()
+        .chain(claimed_tiles.iter().copied())
+        .map(|p| (p, self.tiles[(p.x, p.y)]))
+        .collect(),
+      cathedral_captured: self.cathedral_captured,
+      next_id: self.next_id,
+    };
+
+    self.insert_piece_as(piece, id);
+
+    let mut outcome = PlacementOutcome {
+      placed_id: Some(id),
+      ..PlacementOutcome::default()
+    };
+    for captured_id in captured_ids {
+      let index = self
+        .placement_order
+        .iter()
+        .position(|i| *i == captured_id)
+        .expect("captured piece must be on board");
+      undo.captured.push((
+        index,
+        captured_id,
+        self.pieces[&captured_id].clone(),
+      ));
+      let piece = self.remove_by_id(captured_id)?;
+      if piece.kind() == PieceKind::Cathedral {
+        self.cathedral_captured = true;
+        outcome.cathedral_captured = true;
+      }
+      outcome.captured_pieces.push(piece);
+      outcome.captured_ids.push(captured_id);
+    }
+    self
+      .captures
+      .entry(team)
+      .or_default()
+      .extend(outcome.captured_pieces.iter().map(Piece::kind));
+    for p in &claimed_tiles {
+      self.tiles[(p.x, p.y)] = Tile::Empty(team);
+    }
+    outcome.claimed_tiles = claimed_tiles;
+    Ok((outcome, undo))
+  }
+
+  /// Takes back the last placement, given what it changed. Placements made
+  /// after it must be taken back first.
+  pub(crate) fn undo_placement(&mut self, undo: PlacementUndo) {
+    let id = self
+      .placement_order
+      .pop()
+      .expect("placement must be on board");
+    let piece = self.pieces.remove(&id).expect("piece must be on board");
+    let captures = self.captures.entry(piece.team()).or_default();
+    captures.truncate(captures.len() - undo.captured.len());
+    for (index, id, piece) in undo.captured.into_iter().rev() {
+      self.placement_order.insert(index, id);
+      self.pieces.insert(id, piece);
+    }
+    for (p, tile) in undo.tiles {
+      self.tiles[(p.x, p.y)] = tile;
+    }
+    self.cathedral_captured = undo.cathedral_captured;
+    self.next_id = undo.next_id;
+  }
+
+  /// Returns what placing `piece` at given position would result in, without
+  /// changing the board, including the id the piece would get. Fails the
+  /// same way `try_place_piece` does.
+  pub fn preview_placement(
+    &self,
+    piece: &Piece<Released>,
+    position: Position,
+  ) -> Result<PlacementOutcome, BoardError> {
+    self.can_place_piece(piece, position)?;
+    let (captured_ids, claimed_tiles) =
+      self.resolve_placement(&piece.clone().placed_at(position));
+    let captured_pieces = captured_ids
+      .iter()
+      .map(|id| self.pieces[id].clone().released())
+      .collect::<Vec<_>>();
+    Ok(PlacementOutcome {
+      placed_id: Some(PieceId(self.next_id)),
+      cathedral_captured: captured_pieces
+        .iter()
+        .any(|piece| piece.kind() == PieceKind::Cathedral),
+      captured_pieces,
+      captured_ids,
+      claimed_tiles,
+    })
+  }
 
-    let removed_pieces = Vec::<Piece<Released>>::new();
+  /// Returns ids of pieces that placing `piece` would capture, ordered by
+  /// placement, and tiles it would claim. The piece may or may not be on the
+  /// board already.
+  fn resolve_placement(
+    &self,
+    piece: &Piece<Placed>,
+  ) -> (Vec<PieceId>, HashSet<Position>) {
+    let mut captured_ids = Vec::new();
+    let mut claimed_tiles = HashSet::new();
+    if piece.team() == Team::None {
+      return (captured_ids, claimed_tiles);
+    }
+    for region in self.find_enclosed_tile_sets(piece) {
+      let foreign_ids = self
+        .piece_ids_in(&region)
+        .into_iter()
+        .filter(|id| self.pieces[id].team() != piece.team())
+        .collect::<Vec<_>>();
+      let capturable = match foreign_ids.as_slice() {
+        [] => true,
+        [id] => {
+          self.rules.capture_occupied_regions
+            && (self.rules.cathedral_capturable
+              || self.pieces[id].kind() != PieceKind::Cathedral)
+        }
+        _ => false,
+      };
+      if !capturable {
+        continue;
+      }
+      captured_ids.extend(foreign_ids);
+      claimed_tiles.extend(region);
+    }
+    captured_ids
+      .sort_by_key(|id| self.placement_order.iter().position(|i| i == id));
+    (captured_ids, claimed_tiles)
+  }
+
+  /// Occupies tiles under `piece` and stores it without any checks.
+  /// Returns the id the piece gets.
+  fn insert_piece(&mut self, piece: Piece<Placed>) -> PieceId {
+    let id = PieceId(self.next_id);
+    self.next_id += 1;
+    self.insert_piece_as(piece, id);
+    id
+  }
 
+  /// Occupies tiles under `piece` and stores it with given `id` without any
+  /// checks.
+  fn insert_piece_as(&mut self, piece: Piece<Placed>, id: PieceId) {
     for p in piece.occupied_positions_iter() {
       self.tiles[(p.x, p.y)] = Tile::Occupied(piece.team());
     }
+    self.pieces.insert(id, piece);
+    self.placement_order.push(id);
+  }
 
-    let first_occupied_position = piece
-      .occupied_positions_iter()
-      .next()
-      .expect("piece must occupy at least one tile");
-    self.pieces.insert(first_occupied_position, piece);
+  /// Inserts a piece of `team` covering exactly given `positions`, in
+  /// whatever rotation matches, without any checks. Returns the id the piece
+  /// gets, or `None` if no piece has such a layout.
+  pub(crate) fn insert_piece_covering(
+    &mut self,
+    team: Team,
+    positions: &[Position],
+  ) -> Option<PieceId> {
+    let min_x = positions.iter().map(|p| p.x).min()?;
+    let min_y = positions.iter().map(|p| p.y).min()?;
+    let max_x = positions.iter().map(|p| p.x).max()?;
+    let max_y = positions.iter().map(|p| p.y).max()?;
+    if max_x - min_x >= MAX_PIECE_DIMENSION
+      || max_y - min_y >= MAX_PIECE_DIMENSION
+    {
+      return None;
+    }
+    let layout = positions.iter().fold(
+      Layout::empty(max_x - min_x + 1, max_y - min_y + 1),
+      |l, p| l.with(p.x - min_x, p.y - min_y),
+    );
+    let piece = Piece::from_layout(team, layout)?;
+    Some(self.insert_piece(piece.placed_at(Position { x: min_x, y: min_y })))
+  }
 
-    Ok(removed_pieces)
+  /// Overwrites tile at given position, bypassing any rules.
+  pub(crate) fn set_tile(&mut self, position: Position, tile: Tile) {
+    self.tiles[(position.x, position.y)] = tile;
   }
 
   /// Tries to put piece on board at given position. Panics if it can't.
@@ -97,29 +761,39 @@ impl Board {
     &mut self,
     piece: Piece<Released>,
     position: Position,
-  ) -> Vec<Piece<Released>> {
+  ) -> PlacementOutcome {
     self
       .try_place_piece(piece, position)
       .unwrap_or_else(|e| panic!("could not put piece on the board: {e}"))
   }
 
-  /// Tries to remove piece from board.
+  /// Tries to remove piece covering tile at given `position` from board.
   /// Returns removed piece in `Released` state or an error that occured.
   pub fn try_remove_piece(
     &mut self,
     position: Position,
   ) -> Result<Piece<Released>, BoardError> {
-    let piece = match self.pieces.remove(&position) {
-      Some(piece) => piece,
-      None => return Err(BoardError::PieceNotOnBoard),
-    };
+    let id = self
+      .piece_id_at(position)
+      .ok_or(BoardError::PieceNotOnBoard)?;
+    self.remove_by_id(id)
+  }
+
+  /// Tries to remove piece with given `id` from board.
+  /// Returns removed piece in `Released` state or an error that occured.
+  pub fn remove_by_id(
+    &mut self,
+    id: PieceId,
+  ) -> Result<Piece<Released>, BoardError> {
+    let piece = self.pieces.remove(&id).ok_or(BoardError::PieceNotOnBoard)?;
+    self.placement_order.retain(|i| *i != id);
     for p in piece.occupied_positions_iter() {
       self.tiles[(p.x, p.y)] = Tile::Empty(Team::None);
     }
     Ok(piece.released())
   }
 
-  /// Tries to remove piece from board.
+  /// Tries to remove piece covering tile at given `position` from board.
   /// Panics if it can't. Returns removed piece in `Released` state.
   pub fn remove_piece(&mut self, position: Position) -> Piece<Released> {
     self
@@ -127,123 +801,580 @@ impl Board {
       .unwrap_or_else(|e| panic!("{}", e))
   }
 
-  /// Returns `true` if `position` neighbours a wall tile position.
-  fn near_wall(&self, position: Position) -> bool {
-    let max_position = self.size();
-    position.x == 0
-      || position.y == 0
-      || position.x == max_position.x
-      || position.y == max_position.y
-  }
+  /// Tries to move piece covering tile at `from` so its layout is at `to`,
+  /// rotated to `rotation`. The destination is checked as if the piece was
+  /// not on the board, so it may overlap tiles the piece covers now. On error
+  /// the board is left untouched. Otherwise the move counts as a fresh
+  /// placement: it may capture and the piece becomes the last placed one,
+  /// though it keeps its id. Returns what the placement resulted in.
+  pub fn try_move_piece(
+    &mut self,
+    from: Position,
+    to: Position,
+    rotation: Rotation,
+  ) -> Result<PlacementOutcome, BoardError> {
+    let id = self.piece_id_at(from).ok_or(BoardError::PieceNotOnBoard)?;
+    let index = self
+      .placement_order
+      .iter()
+      .position(|i| *i == id)
+      .expect("piece must be in placement order");
+    let placed = self.pieces[&id].clone();
+    let tiles = placed
+      .occupied_positions_iter()
+      .map(|p| (p, self.tiles[(p.x, p.y)]))
+      .collect::<Vec<_>>();
 
-  /// Returns `true` if tile at given `position` may form enclosing border
-  /// on this `team`'s turn.
-  fn does_position_form_border(&self, position: Position, team: Team) -> bool {
-    matches!(
-      self.tiles[(position.x, position.y)],
-      Tile::Occupied(t) if t == team
-    )
+    let mut piece = self.remove_by_id(id)?;
+    piece.rotate_to(rotation);
+    if let Err(e) = self.can_place_piece(&piece, to) {
+      self.placement_order.insert(index, id);
+      self.pieces.insert(id, placed);
+      for (p, tile) in tiles {
+        self.tiles[(p.x, p.y)] = tile;
+      }
+      return Err(e);
+    }
+    self
+      .try_place_piece_as(piece, to, id)
+      .map(|(outcome, _)| outcome)
   }
 
-  /// Returns `true` if tile at given `position` can be captured by playing a
-  /// piece of `team`.
-  fn is_position_capturable(&self, position: Position, team: Team) -> bool {
-    matches!(
-      self.tiles[(position.x, position.y)],
-      Tile::Empty(t) | Tile::Occupied(t) if t != team
-    )
+  /// Returns positions of all tiles occupied by pieces of given `team`.
+  pub fn footprint(&self, team: Team) -> HashSet<Position> {
+    self
+      .pieces
+      .values()
+      .filter(|piece| piece.team() == team)
+      .flat_map(Piece::<Placed>::occupied_positions_iter)
+      .collect()
   }
 
-  /// Returns a set of unique capturable positions adjacent to given `piece`.
-  fn adjacent_capturable_positions_for_piece(
-    &self,
-    piece: &Piece<Placed>,
-  ) -> HashSet<Position> {
-    piece
-      .occupied_positions_iter()
-      .flat_map(|p| p.diagonal_adjacent_positions_iter(self.size()))
-      .filter(|p| self.is_position_capturable(*p, piece.team()))
-      .collect()
+  /// Returns `team`'s score as it stands on the board. Unplaced squares
+  /// are counted against the standard set of pieces of a two-team game.
+  pub fn score_for(&self, team: Team) -> Score {
+    self.score_in(team, PieceSetPreset::Standard, 2)
   }
 
-  /// Finds and returns a set of tiles in the same group with tile with
-  /// `initial_position`.
-  fn find_tile_set(
+  /// Same as `score_for` but counts unplaced squares against the set `team`
+  /// starts with in a game of `players` teams that uses `preset` sets.
+  pub fn score_in(
     &self,
-    initial_position: Position,
     team: Team,
-  ) -> HashSet<Position> {
-    fn flood_fill_into_set(
-      board: &Board,
-      position: Position,
-      team: Team,
-      set: &mut HashSet<Position>,
-    ) {
-      if set.contains(&position) {
-        return;
+    preset: PieceSetPreset,
+    players: usize,
+  ) -> Score {
+    let placed = self.footprint(team).len();
+    let full = PieceKind::for_team_in(team, preset, players)
+      .iter()
+      .map(|kind| kind.square_count())
+      .sum::<usize>();
+    Score {
+      unplaced_squares: full.saturating_sub(placed),
+      territory: self
+        .tiles
+        .iter()
+        .filter(|tile| **tile == Tile::Empty(team))
+        .count(),
+      captured_pieces: self.captured_by(team).len(),
+    }
+  }
+
+  /// Returns positions of tiles `team` holds as territory: tiles it has
+  /// claimed and empty tiles its pieces wall off from the rest of the board,
+  /// whether or not a placement has claimed them yet. As with captures, the
+  /// largest region is considered the open board and regions tied for it are
+  /// all open. Regions holding pieces of other teams are contested and don't
+  /// count. The cathedral holds no territory.
+  pub fn territory(&self, team: Team) -> HashSet<Position> {
+    let mut territory = HashSet::new();
+    if team == Team::None {
+      return territory;
+    }
+    let is_foreign = |p: Position| {
+      matches!(
+        self.tiles[(p.x, p.y)],
+        Tile::Empty(t) | Tile::Occupied(t) if t != team
+      )
+    };
+
+    let mut regions: Vec<HashSet<Position>> = Vec::new();
+    for (index, tile) in self.tiles.indexed_iter() {
+      let p = Position::from(index);
+      if *tile == Tile::Empty(team) {
+        territory.insert(p);
       }
-      set.insert(position);
-      position
-        .diagonal_adjacent_positions_iter(board.size())
-        .filter(|p| board.is_position_capturable(*p, team))
-        .for_each(|p| flood_fill_into_set(board, p, team, set));
+      if !is_foreign(p) || regions.iter().any(|region| region.contains(&p)) {
+        continue;
+      }
+      let mut region = HashSet::from([p]);
+      let mut queue = vec![p];
+      while let Some(p) = queue.pop() {
+        for n in self.region_adjacent_positions(p) {
+          if is_foreign(n) && region.insert(n) {
+            queue.push(n);
+          }
+        }
+      }
+      regions.push(region);
     }
 
-    let mut set = HashSet::new();
-    flood_fill_into_set(self, initial_position, team, &mut set);
-    set
+    let largest = regions.iter().map(HashSet::len).max().unwrap_or_default();
+    for region in regions {
+      if region.len() < largest
+        && region
+          .iter()
+          .all(|p| matches!(self.tiles[(p.x, p.y)], Tile::Empty(_)))
+      {
+        territory.extend(region);
+      }
+    }
+    territory
   }
 
-  /// Returns sets of capturable tiles' positions.
-  fn find_tile_sets(&self, piece: &Piece<Placed>) -> Vec<HashSet<Position>> {
-    let mut groups: Vec<HashSet<Position>> = Vec::new();
-    let initial_tiles_positions: HashSet<Position> = piece
-      .occupied_positions_iter()
-      .flat_map(|p| p.diagonal_adjacent_positions_iter(self.size()))
-      .filter(|p| self.is_position_capturable(*p, piece.team()))
-      .inspect(|p| println!("{p}"))
-      .collect::<HashSet<_>>();
-    for p in initial_tiles_positions {
-      if !groups.iter().any(|set| set.contains(&p)) {
-        let set = self.find_tile_set(p, piece.team());
-        groups.push(set);
+  /// Returns ids of `team`'s pieces that another team could capture with a
+  /// single legal placement, along with one such placement for each, ordered
+  /// by placement. Other teams are `team`'s opponent and any team with pieces
+  /// on the board. Since the board doesn't know what teams hold in hand,
+  /// each is assumed to hold its standard set minus the pieces it has on the
+  /// board.
+  pub fn threatened_pieces(&self, team: Team) -> Vec<(PieceId, Move)> {
+    let mut threats = Vec::new();
+    if team == Team::None {
+      return threats;
+    }
+    let mut ids = self
+      .placement_order
+      .iter()
+      .copied()
+      .filter(|id| self.pieces[id].team() == team)
+      .collect::<Vec<_>>();
+    let opponents = Team::PLAYERS.into_iter().filter(|t| {
+      *t != team
+        && (*t == team.opponent()
+          || self.pieces.values().any(|p| p.team() == *t))
+    });
+    for opponent in opponents {
+      let mut kinds = PieceKind::for_team(opponent).to_vec();
+      for piece in self.pieces.values().filter(|p| p.team() == opponent) {
+        if let Some(index) = kinds.iter().position(|k| *k == piece.kind()) {
+          kinds.remove(index);
+        }
+      }
+      kinds.sort();
+      kinds.dedup();
+      for kind in kinds {
+        let piece = Piece::new(kind, opponent);
+        for (rotation, position) in self.legal_placements(&piece) {
+          if ids.is_empty() {
+            break;
+          }
+          let mut piece = piece.clone();
+          piece.rotate_to(rotation);
+          let Ok(outcome) = self.preview_placement(&piece, position) else {
+            continue;
+          };
+          ids.retain(|id| {
+            let captured = outcome.captured_ids.contains(id);
+            if captured {
+              threats.push((*id, Move::Place {
+                team: opponent,
+                kind,
+                rotation,
+                position,
+              }));
+            }
+            !captured
+          });
+        }
       }
     }
-    groups
+    threats
+      .sort_by_key(|(id, _)| self.placement_order.iter().position(|i| i == id));
+    threats
   }
-}
 
-impl Default for Board {
-  fn default() -> Self {
-    Self::with_size(10)
+  /// Returns kinds of pieces `team` has captured, in order of capture.
+  pub fn captured_by(&self, team: Team) -> &[PieceKind] {
+    self.captures.get(&team).map_or(&[], Vec::as_slice)
   }
-}
 
-impl Display for Board {
-  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-    write!(f, "\n   ")?;
-    for i in 0..self.tiles.ncols() {
-      write!(f, "{i:>2}")?;
+  /// Splits `team`'s footprint into clusters of tiles connected by their
+  /// sides. Clusters are ordered by their smallest position.
+  pub fn contiguous_footprints(&self, team: Team) -> Vec<HashSet<Position>> {
+    let mut footprint = self.footprint(team);
+    let mut clusters = Vec::new();
+    while let Some(&start) = footprint.iter().min_by_key(|p| (p.x, p.y)) {
+      let mut cluster = HashSet::new();
+      let mut stack = vec![start];
+      while let Some(p) = stack.pop() {
+        if footprint.remove(&p) {
+          cluster.insert(p);
+          stack.extend(p.orthogonal_adjacent_positions_iter(self.size()));
+        }
+      }
+      clusters.push(cluster);
+    }
+    clusters
+  }
+
+  /// Returns cathedral's status on this board.
+  pub fn cathedral_status(&self) -> CathedralStatus {
+    if self.cathedral_captured {
+      return CathedralStatus::Captured;
     }
-    writeln!(f)?;
     self
-      .tiles
-      .rows()
-      .into_iter()
-      .enumerate()
-      .try_for_each(|(i, row)| {
-        if (0..self.tiles.nrows()).contains(&i) {
-          write!(f, "{:>2} ", i)?;
-        }
+      .pieces
+      .values()
+      .find(|piece| piece.kind() == PieceKind::Cathedral)
+      .map_or(CathedralStatus::InBox, |piece| {
+        CathedralStatus::Placed(piece.position())
+      })
+  }
+
+  /// Tries to remove the cathedral from board.
+  /// Returns removed cathedral in `Released` state or an error if it is not on
+  /// the board.
+  pub fn remove_cathedral(&mut self) -> Result<Piece<Released>, BoardError> {
+    let id = self
+      .pieces
+      .iter()
+      .find(|(_, piece)| piece.kind() == PieceKind::Cathedral)
+      .map(|(id, _)| *id)
+      .ok_or(BoardError::PieceNotOnBoard)?;
+    self.remove_by_id(id)
+  }
+
+  /// Returns counts of each team's tiles on every row and every column, in
+  /// a single pass over the board. The cathedral and neutral tiles are not
+  /// counted.
+  pub fn line_counts(&self) -> (Vec<LineCounts>, Vec<LineCounts>) {
+    let (rows, cols) = self.tiles.dim();
+    let mut row_counts = vec![LineCounts::default(); rows];
+    let mut col_counts = vec![LineCounts::default(); cols];
+    for ((x, y), tile) in self.tiles.indexed_iter() {
+      row_counts[x].add(*tile);
+      col_counts[y].add(*tile);
+    }
+    (row_counts, col_counts)
+  }
+
+  /// Returns counts of each team's tiles on every row, from row 0.
+  pub fn row_counts(&self) -> Vec<LineCounts> {
+    self.line_counts().0
+  }
+
+  /// Returns counts of each team's tiles on every column, from column 0.
+  pub fn col_counts(&self) -> Vec<LineCounts> {
+    self.line_counts().1
+  }
+
+  /// Returns `true` if `position` lies on the board's edge, i.e. its tile
+  /// neighbours a wall. Positions out of board's bounds are not edges.
+  /// Captures don't rely on it: region flood fills never leave the board,
+  /// so its edges already wall regions off.
+  pub fn is_edge(&self, position: Position) -> bool {
+    let size = self.size();
+    position.x < size.x
+      && position.y < size.y
+      && (position.x == 0
+        || position.y == 0
+        || position.x == size.x - 1
+        || position.y == size.y - 1)
+  }
+
+  /// Returns iterator of all positions that lie on the board's edge.
+  /// Each position is returned once.
+  pub fn edge_positions(&self) -> impl Iterator<Item = Position> + '_ {
+    let size = self.size();
+    (0..size.x)
+      .flat_map(move |x| (0..size.y).map(move |y| Position { x, y }))
+      .filter(|p| self.is_edge(*p))
+  }
+
+  /// Returns symmetries that leave the board unchanged: every tile maps onto
+  /// an equal one and every piece onto a piece of the same kind and team.
+  /// `Symmetry::Identity` is always among them.
+  pub fn symmetries(&self) -> Vec<Symmetry> {
+    let size = self.size().x;
+    let footprints = self
+      .pieces
+      .values()
+      .map(|piece| {
+        let mut positions = piece.occupied_positions_iter().collect::<Vec<_>>();
+        positions.sort();
+        (positions, (piece.kind(), piece.team()))
+      })
+      .collect::<HashMap<_, _>>();
+    Symmetry::ALL
+      .into_iter()
+      .filter(|symmetry| {
+        self.tiles.indexed_iter().all(|(p, tile)| {
+          let q = symmetry.apply(Position::from(p), size);
+          self.tiles[(q.x, q.y)] == *tile
+        }) && footprints.iter().all(|(positions, piece)| {
+          let mut mapped = positions
+            .iter()
+            .map(|p| symmetry.apply(*p, size))
+            .collect::<Vec<_>>();
+          mapped.sort();
+          footprints.get(&mapped) == Some(piece)
+        })
+      })
+      .collect()
+  }
+
+  /// Checks that tiles agree with pieces on the board: every occupied tile
+  /// is covered by exactly one piece of the same team, pieces have ids the
+  /// board gave out and are listed once in placement order, and claimed
+  /// tiles are walled off from neutral ones. Boards changed only
+  /// by placements and captures always pass.
+  pub fn validate(&self) -> Result<(), InvalidBoardError> {
+    let mut covered = HashMap::new();
+    for (id, piece) in &self.pieces {
+      if id.0 >= self.next_id {
+        return Err(InvalidBoardError::UnknownId(*id));
+      }
+      for p in piece.occupied_positions_iter() {
+        if self.tiles.get((p.x, p.y)).is_none() {
+          return Err(InvalidBoardError::PieceOutOfBounds(p));
+        }
+        if covered.insert(p, piece.team()).is_some() {
+          return Err(InvalidBoardError::OverlappingPieces(p));
+        }
+      }
+    }
+
+    let mut order = self.placement_order.clone();
+    order.sort();
+    order.dedup();
+    if order.len() != self.placement_order.len()
+      || order.len() != self.pieces.len()
+      || !order.iter().all(|id| self.pieces.contains_key(id))
+    {
+      return Err(InvalidBoardError::PlacementOrder);
+    }
+
+    for (index, tile) in self.tiles.indexed_iter() {
+      let p = Position::from(index);
+      match (tile, covered.get(&p)) {
+        (Tile::Occupied(team), Some(t)) if team == t => (),
+        (Tile::Occupied(_), _) | (_, Some(_)) => {
+          return Err(InvalidBoardError::TileMismatch(p))
+        }
+        _ => (),
+      }
+    }
+
+    let mut checked = HashSet::new();
+    for (index, tile) in self.tiles.indexed_iter() {
+      let p = Position::from(index);
+      if *tile == Tile::Empty(Team::None)
+        || !matches!(tile, Tile::Empty(_))
+        || checked.contains(&p)
+      {
+        continue;
+      }
+      let region = self.empty_region(p);
+      if region
+        .iter()
+        .any(|p| self.tiles[(p.x, p.y)] == Tile::Empty(Team::None))
+      {
+        return Err(InvalidBoardError::OpenClaim(p));
+      }
+      checked.extend(region);
+    }
+    Ok(())
+  }
+
+  /// Returns positions of empty tiles reachable from `position` without
+  /// crossing occupied or blocked tiles, according to the board's region
+  /// connectivity rule.
+  fn empty_region(&self, position: Position) -> HashSet<Position> {
+    let mut region = HashSet::from([position]);
+    let mut queue = vec![position];
+    while let Some(p) = queue.pop() {
+      for n in self.region_adjacent_positions(p) {
+        if matches!(self.tiles[(n.x, n.y)], Tile::Empty(_)) && region.insert(n)
+        {
+          queue.push(n);
+        }
+      }
+    }
+    region
+  }
+
+  /// Returns `true` if tile at given `position` can be captured by playing
+  /// `piece`. Tiles under the piece count as its team's, whether it is on the
+  /// board or not.
+  fn is_position_capturable(
+    &self,
+    position: Position,
+    piece: &Piece<Placed>,
+  ) -> bool {
+    matches!(
+      self.tiles[(position.x, position.y)],
+      Tile::Empty(t) | Tile::Occupied(t) if t != piece.team()
+    ) && !piece.occupied_positions_iter().any(|p| p == position)
+  }
+
+  /// Returns a set of unique capturable positions adjacent to given `piece`.
+  fn adjacent_capturable_positions_for_piece(
+    &self,
+    piece: &Piece<Placed>,
+  ) -> HashSet<Position> {
+    piece
+      .occupied_positions_iter()
+      .flat_map(|p| p.diagonal_adjacent_positions_iter(self.size()))
+      .filter(|p| self.is_position_capturable(*p, piece))
+      .collect()
+  }
+
+  /// Returns positions adjacent to given `position` that belong to the same
+  /// region as it would, according to the board's region connectivity rule.
+  fn region_adjacent_positions(&self, position: Position) -> Vec<Position> {
+    match self.rules.region_connectivity {
+      Connectivity::Orthogonal => position
+        .orthogonal_adjacent_positions_iter(self.size())
+        .collect(),
+      Connectivity::Diagonal => position
+        .diagonal_adjacent_positions_iter(self.size())
+        .collect(),
+    }
+  }
+
+  /// Finds and returns a set of tiles in the same group with tile with
+  /// `initial_position`.
+  fn find_tile_set(
+    &self,
+    initial_position: Position,
+    piece: &Piece<Placed>,
+  ) -> HashSet<Position> {
+    fn flood_fill_into_set(
+      board: &Board,
+      position: Position,
+      piece: &Piece<Placed>,
+      set: &mut HashSet<Position>,
+    ) {
+      if set.contains(&position) {
+        return;
+      }
+      set.insert(position);
+      board
+        .region_adjacent_positions(position)
+        .into_iter()
+        .filter(|p| board.is_position_capturable(*p, piece))
+        .for_each(|p| flood_fill_into_set(board, p, piece, set));
+    }
+
+    let mut set = HashSet::new();
+    flood_fill_into_set(self, initial_position, piece, &mut set);
+    set
+  }
+
+  /// Returns sets of capturable tiles' positions.
+  fn find_tile_sets(&self, piece: &Piece<Placed>) -> Vec<HashSet<Position>> {
+    let mut groups: Vec<HashSet<Position>> = Vec::new();
+    let initial_tiles_positions =
+      self.adjacent_capturable_positions_for_piece(piece);
+    for p in initial_tiles_positions {
+      if !groups.iter().any(|set| set.contains(&p)) {
+        let set = self.find_tile_set(p, piece);
+        groups.push(set);
+      }
+    }
+    groups
+  }
+
+  /// Returns sets of capturable tiles' positions that `piece` walls off from
+  /// the rest of the board. If the piece splits capturable tiles into several
+  /// sets, the largest of them is considered the open board and all the
+  /// others are enclosed. Sets tied for the largest size are all considered
+  /// open.
+  fn find_enclosed_tile_sets(
+    &self,
+    piece: &Piece<Placed>,
+  ) -> Vec<HashSet<Position>> {
+    let mut sets = self.find_tile_sets(piece);
+    let largest = sets.iter().map(HashSet::len).max().unwrap_or_default();
+    sets.retain(|set| set.len() < largest);
+    sets
+  }
+
+  /// Returns ids of pieces that occupy at least one tile in `region`,
+  /// ordered by placement.
+  fn piece_ids_in(&self, region: &HashSet<Position>) -> Vec<PieceId> {
+    self
+      .placement_order
+      .iter()
+      .filter(|id| {
+        self.pieces[id]
+          .occupied_positions_iter()
+          .any(|p| region.contains(&p))
+      })
+      .copied()
+      .collect()
+  }
+}
+
+impl Default for Board {
+  fn default() -> Self {
+    Self::with_size(STANDARD_BOARD_SIZE)
+  }
+}
+
+impl Index<Position> for Board {
+  type Output = Tile;
+
+  /// Returns tile at given `position`. Panics if it is out of bounds.
+  fn index(&self, position: Position) -> &Tile {
+    &self.tiles[(position.x, position.y)]
+  }
+}
+
+impl Display for Board {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "\n   ")?;
+    for i in 0..self.tiles.ncols() {
+      write!(f, "{i:>2}")?;
+    }
+    writeln!(f)?;
+    self
+      .tiles
+      .rows()
+      .into_iter()
+      .enumerate()
+      .try_for_each(|(i, row)| {
+        if (0..self.tiles.nrows()).contains(&i) {
+          write!(f, "{:>2} ", i)?;
+        }
         row.iter().try_for_each(|cell| write!(f, "{cell}"))?;
         writeln!(f)
       })
   }
 }
 
+#[cfg(test)]
+impl Board {
+  /// Overwrites tiles at given positions, bypassing any rules.
+  pub(crate) fn set_tiles_for_test(
+    &mut self,
+    positions: &[(usize, usize)],
+    tile: Tile,
+  ) {
+    for p in positions {
+      self.tiles[*p] = tile;
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::{
+    consts::{PIECES_PER_TEAM, SQUARES_PER_TEAM},
+    testing::{BoardBuilder, Scenario},
+  };
 
   #[test]
   fn test_max_position() {
@@ -254,6 +1385,136 @@ mod tests {
     assert_eq!(board.size(), (5, 5).into());
   }
 
+  #[test]
+  fn test_tiles_iter() {
+    let mut board = Board::with_size(3);
+    board.place_piece(Piece::new_stable(Team::Black), (0, 1).into());
+    assert_eq!((board.rows(), board.cols()), (3, 3));
+    let tiles = board.tiles_iter().collect::<Vec<_>>();
+    assert_eq!(tiles.len(), 9);
+    assert_eq!(tiles[0], ((0, 0).into(), Tile::Empty(Team::None)));
+    assert_eq!(tiles[1], ((0, 1).into(), Tile::Occupied(Team::Black)));
+    assert_eq!(tiles[4], ((1, 1).into(), Tile::Occupied(Team::Black)));
+    assert_eq!(tiles[8].0, (2, 2).into());
+  }
+
+  #[test]
+  fn test_index() {
+    let mut board = Board::with_size(3);
+    board.place_piece(Piece::new_tavern(Team::White), (1, 2).into());
+    assert_eq!(board[(1, 2).into()], Tile::Occupied(Team::White));
+    assert_eq!(board[(0, 0).into()], Tile::Empty(Team::None));
+    assert_eq!(board.get((1, 2).into()), Some(Tile::Occupied(Team::White)));
+    assert_eq!(board.get((3, 0).into()), None);
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_index_out_of_bounds() {
+    let _ = Board::with_size(3)[(0, 3).into()];
+  }
+
+  #[test]
+  fn test_is_edge() {
+    let board = Board::with_size(10);
+    assert!(board.is_edge((0, 0).into()));
+    assert!(board.is_edge((0, 5).into()));
+    assert!(board.is_edge((5, 0).into()));
+    assert!(board.is_edge((9, 5).into()));
+    assert!(board.is_edge((5, 9).into()));
+    assert!(board.is_edge((9, 9).into()));
+    assert!(!board.is_edge((1, 1).into()));
+    assert!(!board.is_edge((8, 8).into()));
+    assert!(!board.is_edge((10, 5).into()));
+    assert!(!board.is_edge((5, 10).into()));
+  }
+
+  #[test]
+  fn test_edge_positions() {
+    let board = Board::with_size(10);
+    let edges = board.edge_positions().collect::<Vec<_>>();
+    assert_eq!(edges.len(), 36);
+    assert_eq!(edges.len(), edges.iter().collect::<HashSet<_>>().len());
+    assert!(edges.iter().all(|p| board.is_edge(*p)));
+
+    let board = Board::with_size(1);
+    assert_eq!(board.edge_positions().collect::<Vec<_>>(), [(0, 0).into()]);
+  }
+
+  #[test]
+  fn test_line_counts() {
+    let board = BoardBuilder::new(
+      "
+      w W B .
+      W W B .
+      . . b B
+      . X . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let (rows, cols) = board.line_counts();
+    assert_eq!(rows, board.row_counts());
+    assert_eq!(cols, board.col_counts());
+    assert_eq!(rows[0], LineCounts {
+      white: TeamTiles {
+        occupied: 1,
+        claimed: 1,
+      },
+      black: TeamTiles {
+        occupied: 1,
+        claimed: 0,
+      },
+      ..LineCounts::default()
+    });
+    assert_eq!(rows[2].team(Team::Black), TeamTiles {
+      occupied: 1,
+      claimed: 1,
+    });
+    assert_eq!(rows[3], LineCounts::default());
+    assert_eq!(cols[1].team(Team::White).occupied, 2);
+    assert_eq!(cols[2].team(Team::Black).occupied, 2);
+    assert_eq!(cols[3].team(Team::None), TeamTiles::default());
+  }
+
+  #[test]
+  fn test_symmetries() -> Result<(), BoardError> {
+    assert_eq!(Symmetry::Rotate90.apply((0, 0).into(), 10), Position {
+      x: 0,
+      y: 9
+    });
+    assert_eq!(Symmetry::AntiTranspose.apply((0, 2).into(), 10), Position {
+      x: 7,
+      y: 9
+    });
+
+    let mut board = Board::with_size(10);
+    assert_eq!(board.symmetries(), Symmetry::ALL);
+
+    board.try_place_piece(Piece::new_tavern(Team::White), (0, 0).into())?;
+    assert_eq!(board.symmetries(), [
+      Symmetry::Identity,
+      Symmetry::Transpose
+    ]);
+
+    board.try_place_piece(Piece::new_tavern(Team::Black), (9, 9).into())?;
+    assert_eq!(board.symmetries(), [
+      Symmetry::Identity,
+      Symmetry::Transpose
+    ]);
+
+    let mut board = Board::with_size(10);
+    board.try_place_piece(Piece::new_tavern(Team::White), (0, 0).into())?;
+    board.try_place_piece(Piece::new_tavern(Team::White), (9, 9).into())?;
+    assert_eq!(board.symmetries(), [
+      Symmetry::Identity,
+      Symmetry::Rotate180,
+      Symmetry::Transpose,
+      Symmetry::AntiTranspose
+    ]);
+    Ok(())
+  }
+
   #[test]
   fn test_interactive_tiles_empty_on_empty_board() {
     let board = Board::default();
@@ -299,6 +1560,184 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_quick_reject() {
+    let mut board = Board::default();
+    let cathedral = Piece::new_cathedral();
+    assert!(!board.quick_reject(&cathedral, (0, 0).into()));
+    assert!(!board.quick_reject(&cathedral, (6, 7).into()));
+    assert!(board.quick_reject(&cathedral, (7, 7).into()));
+    assert!(board.quick_reject(&cathedral, (6, 8).into()));
+    assert!(board.quick_reject(&cathedral, (usize::MAX, 0).into()));
+
+    board.place_piece(Piece::new_tavern(Team::White), (1, 1).into());
+    assert!(board.quick_reject(&cathedral, (0, 0).into()));
+    assert!(!board.quick_reject(&cathedral, (0, 2).into()));
+  }
+
+  #[test]
+  fn test_reset() {
+    let mut board = Board::default();
+    board.scatter_ruins(7, 5);
+    board.place_piece(Piece::new_tavern(Team::White), (0, 1).into());
+    board.place_piece(Piece::new_tavern(Team::White), (1, 0).into());
+    let rules = Rules {
+      region_connectivity: Connectivity::Diagonal,
+      ..Rules::default()
+    };
+    board.reset_with(rules);
+    assert!(board.tiles.iter().all(|t| *t == Tile::Empty(Team::None)));
+    assert!(board.pieces.is_empty());
+    assert_eq!(board.pieces_in_placement_order().count(), 0);
+    assert_eq!(board.rules(), rules);
+    // Allocations are kept for reuse.
+    assert!(board.memory_footprint() > Board::default().memory_footprint());
+
+    board.reset();
+    assert_eq!(board.rules(), rules);
+  }
+
+  #[test]
+  fn test_validate() {
+    let mut board = BoardBuilder::new(
+      "
+      w W . .
+      W W . .
+      . . B .
+      . . . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert_eq!(board.validate(), Ok(()));
+
+    board.set_tile((3, 3).into(), Tile::Occupied(Team::Black));
+    assert_eq!(
+      board.validate(),
+      Err(InvalidBoardError::TileMismatch((3, 3).into()))
+    );
+    board.set_tile((3, 3).into(), Tile::Empty(Team::None));
+    board.set_tile((2, 2).into(), Tile::Occupied(Team::White));
+    assert_eq!(
+      board.validate(),
+      Err(InvalidBoardError::TileMismatch((2, 2).into()))
+    );
+    board.set_tile((2, 2).into(), Tile::Occupied(Team::Black));
+
+    let id = board.placement_order.pop().expect("must have pieces");
+    assert_eq!(board.validate(), Err(InvalidBoardError::PlacementOrder));
+    board.placement_order.push(id);
+
+    let piece = board.pieces.remove(&id).expect("must be on board");
+    board.pieces.insert(PieceId(99), piece);
+    assert_eq!(
+      board.validate(),
+      Err(InvalidBoardError::UnknownId(PieceId(99)))
+    );
+    let piece = board.pieces.remove(&PieceId(99)).expect("must be there");
+    board.pieces.insert(id, piece);
+
+    board.insert_piece(Piece::new_stable(Team::Black).placed_at((1, 2).into()));
+    assert_eq!(
+      board.validate(),
+      Err(InvalidBoardError::OverlappingPieces((2, 2).into()))
+    );
+    board.remove_piece((1, 2).into());
+    board.set_tile((2, 2).into(), Tile::Occupied(Team::Black));
+    assert_eq!(board.validate(), Ok(()));
+
+    board.remove_piece((0, 1).into());
+    assert_eq!(
+      board.validate(),
+      Err(InvalidBoardError::OpenClaim((0, 0).into()))
+    );
+  }
+
+  #[test]
+  fn test_clear() {
+    let mut board = Board::with_size(4);
+    board.place_piece(Piece::new_stable(Team::Black), (0, 0).into());
+    board.place_piece(Piece::new_tavern(Team::White), (3, 3).into());
+    board.scatter_ruins(1, 2);
+    assert_eq!(board.clear(), [
+      Piece::new_stable(Team::Black),
+      Piece::new_tavern(Team::White)
+    ]);
+    assert!(board
+      .tiles_iter()
+      .all(|(_, t)| t == Tile::Empty(Team::None)));
+    assert_eq!(board.pieces_iter().count(), 0);
+    assert!(board.clear().is_empty());
+  }
+
+  #[test]
+  fn test_memory_footprint() {
+    let mut board = Board::default();
+    let empty = board.memory_footprint();
+    assert!(empty >= 100 * mem::size_of::<Tile>());
+    assert!(Board::with_size(20).memory_footprint() > empty);
+
+    board.place_piece(Piece::new_castle(Team::White), (0, 0).into());
+    assert!(board.memory_footprint() > empty);
+
+    board.place_piece(Piece::new_tavern(Team::Black), (9, 9).into());
+    board.place_piece(Piece::new_tavern(Team::White), (9, 8).into());
+    board.place_piece(Piece::new_tavern(Team::White), (8, 9).into());
+    assert_eq!(board.captured_by(Team::White), [PieceKind::Tavern]);
+    let with_captures = board.memory_footprint();
+    board.captures = HashMap::new();
+    assert!(board.memory_footprint() < with_captures);
+  }
+
+  #[test]
+  fn test_legal_placements() {
+    let mut board = Board::with_size(3);
+    let bridge = Piece::new_bridge(Team::White);
+    assert_eq!(board.legal_placements(&bridge).len(), 6);
+
+    board.place_piece(Piece::new_tavern(Team::Black), (1, 1).into());
+    let placements = board.legal_placements(&bridge);
+    assert_eq!(placements.len(), 4);
+    for (rotation, position) in placements {
+      let mut bridge = bridge.clone();
+      bridge.rotate_to(rotation);
+      assert_eq!(board.can_place_piece(&bridge, position), Ok(()));
+    }
+    assert!(board.legal_placements(&Piece::new_cathedral()).is_empty());
+  }
+
+  #[test]
+  fn test_legal_placements_in() {
+    let mut board = Board::default();
+    let region = (0..2)
+      .flat_map(|x| (0..3).map(move |y| Position { x, y }))
+      .collect::<HashSet<_>>();
+
+    let tavern = Piece::new_tavern(Team::White);
+    assert_eq!(board.legal_placements_in(&tavern, &region).len(), 6);
+    assert!(board
+      .legal_placements_in(&tavern, &HashSet::new())
+      .is_empty());
+
+    let stable = Piece::new_stable(Team::White);
+    assert_eq!(board.legal_placements_in(&stable, &region).len(), 7);
+
+    let manor = Piece::new_manor(Team::White);
+    assert_eq!(board.legal_placements_in(&manor, &region), [
+      (Rotation::UP, (0, 0).into()),
+      (Rotation::DOWN, (0, 0).into()),
+    ]);
+
+    board.place_piece(Piece::new_tavern(Team::Black), (0, 1).into());
+    assert!(board.legal_placements_in(&manor, &region).is_empty());
+    assert_eq!(board.legal_placements_in(&stable, &region), [
+      (Rotation::UP, (0, 0).into()),
+      (Rotation::UP, (0, 2).into()),
+      (Rotation::RIGHT, (1, 0).into()),
+      (Rotation::RIGHT, (1, 1).into()),
+    ]);
+  }
+
   #[test]
   fn test_try_place_piece() -> Result<(), BoardError> {
     let mut board = Board::default();
@@ -333,102 +1772,122 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_piece_at() -> Result<(), BoardError> {
+    let mut board = Board::with_size(4);
+    board.place_piece(Piece::new_stable(Team::White), (1, 1).into());
+    let placed = Piece::new_stable(Team::White).placed_at((1, 1).into());
+    assert_eq!(board.piece_at((1, 1).into()), Some(&placed));
+    assert_eq!(board.piece_at((2, 1).into()), Some(&placed));
+    assert_eq!(board.piece_at((0, 1).into()), None);
+    assert_eq!(board.piece_at((9, 9).into()), None);
+
+    board.try_move_piece((2, 1).into(), (0, 0).into(), Rotation::RIGHT)?;
+    assert_eq!(
+      board.piece_at((0, 1).into()).map(Piece::kind),
+      Some(PieceKind::Stable)
+    );
+    let mut stable = Piece::new_stable(Team::White);
+    stable.rotate_to(Rotation::RIGHT);
+    assert_eq!(board.try_remove_piece((0, 1).into())?, stable);
+    assert_eq!(
+      board.try_remove_piece((0, 1).into()),
+      Err(BoardError::PieceNotOnBoard)
+    );
+    assert!(board.pieces.is_empty());
+    assert_eq!(board.validate(), Ok(()));
+    Ok(())
+  }
+
+  #[test]
+  fn test_try_move_piece() -> Result<(), BoardError> {
+    let mut board = BoardBuilder::new(
+      "
+      W1 W1 W1 . .
+      W1 .  W1 . .
+      .  .  .  . .
+      .  .  .  . .
+      .  .  B  . W2
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+
+    // The castle shifts down onto tiles it covers now.
+    board.try_move_piece((0, 0).into(), (1, 0).into(), Rotation::UP)?;
+    assert_eq!(board.tiles[(0, 0)], Tile::Empty(Team::None));
+    assert_eq!(board.tiles[(1, 0)], Tile::Occupied(Team::White));
+    assert_eq!(board.tiles[(2, 2)], Tile::Occupied(Team::White));
+    assert_eq!(
+      board.pieces_in_placement_order().last().map(Piece::kind),
+      Some(PieceKind::Castle)
+    );
+
+    // Illegal moves leave the board as it was.
+    let tiles = board.tiles.clone();
+    let placement_order = board.placement_order.clone();
+    assert_eq!(
+      board.try_move_piece((1, 0).into(), (3, 0).into(), Rotation::UP),
+      Err(BoardError::PieceOnOccupiedTile((4, 2).into()))
+    );
+    assert_eq!(board.tiles, tiles);
+    assert_eq!(board.placement_order, placement_order);
+    assert_eq!(
+      board.try_move_piece((0, 0).into(), (1, 0).into(), Rotation::UP),
+      Err(BoardError::PieceNotOnBoard)
+    );
+
+    // Moved pieces capture like placed ones.
+    let outcome =
+      board.try_move_piece((1, 0).into(), (3, 1).into(), Rotation::UP)?;
+    assert_eq!(outcome.captured_pieces, [Piece::new_tavern(Team::Black)]);
+    assert_eq!(board.tiles[(4, 2)], Tile::Empty(Team::White));
+    assert_eq!(board.pieces.len(), 2);
+    Ok(())
+  }
+
   /// Test if it is possible to fill the enitre board using all white pieces,
   /// black pieces and the cathedral. There should be no empty tiles left.
   /// Then removes each placed piece from the board and check if the board is
   /// empty.
   #[test]
   fn test_fill_and_free_board() -> Result<(), BoardError> {
-    let w_tavern1 = Piece::new_tavern(Team::White);
-    let w_tavern2 = Piece::new_tavern(Team::White);
-    let w_stable1 = Piece::new_stable(Team::White);
-    let mut w_stable2 = Piece::new_stable(Team::White);
-    let w_inn1 = Piece::new_inn(Team::White);
-    let mut w_inn2 = Piece::new_inn(Team::White);
-    let w_bridge = Piece::new_bridge(Team::White);
-    let w_square = Piece::new_square(Team::White);
-    let mut w_manor = Piece::new_manor(Team::White);
-    let w_abbey = Piece::new_abbey(Team::White);
-    let mut w_academy = Piece::new_academy(Team::White);
-    let w_infirmary = Piece::new_infirmary(Team::White);
-    let mut w_castle = Piece::new_castle(Team::White);
-    let mut w_tower = Piece::new_tower(Team::White);
-
-    let b_tavern1 = Piece::new_tavern(Team::Black);
-    let b_tavern2 = Piece::new_tavern(Team::Black);
-    let mut b_stable1 = Piece::new_stable(Team::Black);
-    let mut b_stable2 = Piece::new_stable(Team::Black);
-    let mut b_inn1 = Piece::new_inn(Team::Black);
-    let mut b_inn2 = Piece::new_inn(Team::Black);
-    let b_bridge = Piece::new_bridge(Team::Black);
-    let b_square = Piece::new_square(Team::Black);
-    let mut b_manor = Piece::new_manor(Team::Black);
-    let mut b_abbey = Piece::new_abbey(Team::Black);
-    let mut b_academy = Piece::new_academy(Team::Black);
-    let b_infirmary = Piece::new_infirmary(Team::Black);
-    let mut b_castle = Piece::new_castle(Team::Black);
-    let mut b_tower = Piece::new_tower(Team::Black);
-
-    let cathedral = Piece::new_cathedral();
-
-    let mut board = Board::default();
+    let mut board = BoardBuilder::new(
+      "
+      W1 W2 W2 W1 W2 W2 W1 W2 W2 B1
+      W2 W2 W3 W1 W4 W1 W1 W2 W2 B1
+      W1 W3 W3 W3 W4 W4 W1 W1 B2 B1
+      W2 W2 W1 W2 W2 W4 W4 B1 B3 B3
+      W2 W1 W1 W1 W2 B2 B1 B1 B1 B3
+      W1 W2 W1 W2 W2 B2 B2 C  B3 B3
+      W1 W2 W2 B1 B3 B3 C  C  C  B1
+      W1 B2 B1 B1 B1 B3 B3 C  B1 B1
+      B2 B2 B2 B1 B2 B2 B3 C  B1 B2
+      B1 B1 B2 B3 B2 B2 B1 B1 B2 B2
+      ",
+    )
+    .build()
+    .expect("must be valid art");
 
-    board.try_place_piece(w_tavern1, (0, 0).into())?;
-    board.try_place_piece(w_abbey, (0, 0).into())?;
-    board.try_place_piece(w_stable1, (0, 3).into())?;
-    w_stable2.rotate_clockwise();
-    board.try_place_piece(w_stable2, (0, 4).into())?;
-    w_academy.rotate_clockwise();
-    board.try_place_piece(w_academy, (0, 5).into())?;
-    board.try_place_piece(w_square, (0, 7).into())?;
-    board.try_place_piece(w_tavern2, (2, 0).into())?;
-    w_manor.rotate_clockwise();
-    w_manor.rotate_clockwise();
-    board.try_place_piece(w_manor, (1, 1).into())?;
-    w_tower.rotate_counterclockwise();
-    board.try_place_piece(w_tower, (1, 4).into())?;
-    board.try_place_piece(w_inn1, (3, 0).into())?;
-    board.try_place_piece(w_infirmary, (3, 1).into())?;
-    w_castle.rotate_clockwise();
-    board.try_place_piece(w_castle, (3, 3).into())?;
-    board.try_place_piece(w_bridge, (5, 0).into())?;
-    w_inn2.rotate_counterclockwise();
-    board.try_place_piece(w_inn2, (5, 1).into())?;
-
-    board.try_place_piece(b_bridge, (0, 9).into())?;
-    board.try_place_piece(b_tavern1, (2, 8).into())?;
-    b_manor.rotate_clockwise();
-    b_manor.rotate_clockwise();
-    board.try_place_piece(b_manor, (3, 6).into())?;
-    b_castle.rotate_clockwise();
-    board.try_place_piece(b_castle, (3, 8).into())?;
-    b_inn1.rotate_counterclockwise();
-    board.try_place_piece(b_inn1, (4, 5).into())?;
-    board.try_place_piece(b_infirmary, (6, 2).into())?;
-    b_tower.rotate_clockwise();
-    board.try_place_piece(b_tower, (6, 4).into())?;
-    b_abbey.rotate_clockwise();
-    board.try_place_piece(b_abbey, (6, 8).into())?;
-    b_academy.rotate_clockwise();
-    b_academy.rotate_clockwise();
-    board.try_place_piece(b_academy, (7, 0).into())?;
-    board.try_place_piece(b_square, (8, 4).into())?;
-    b_stable1.rotate_clockwise();
-    board.try_place_piece(b_stable1, (9, 0).into())?;
-    board.try_place_piece(b_tavern2, (9, 3).into())?;
-    b_stable2.rotate_clockwise();
-    board.try_place_piece(b_stable2, (9, 6).into())?;
-    b_inn2.rotate_clockwise();
-    b_inn2.rotate_clockwise();
-    board.try_place_piece(b_inn2, (8, 8).into())?;
-
-    board.try_place_piece(cathedral, (5, 6).into())?;
+    for team in [Team::White, Team::Black, Team::None] {
+      let mut kinds = board
+        .pieces
+        .values()
+        .filter(|p| p.team() == team)
+        .map(Piece::kind)
+        .collect::<Vec<_>>();
+      kinds.sort();
+      let mut set = PieceKind::for_team(team).to_vec();
+      set.sort();
+      assert_eq!(kinds, set);
+    }
 
+    assert_eq!(board.pieces.len(), 2 * PIECES_PER_TEAM + 1);
     assert!(board.tiles.iter().all(|t| matches!(t, Tile::Occupied(_))));
 
-    let piece_pos = board.pieces.clone().into_iter().collect::<Vec<_>>();
-    for (pos, _) in piece_pos.into_iter() {
-      board.try_remove_piece(pos)?;
+    let ids = board.pieces.keys().copied().collect::<Vec<_>>();
+    for id in ids {
+      board.remove_by_id(id)?;
     }
     assert!(board
       .tiles
@@ -438,11 +1897,717 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_pieces_in_placement_order() {
+    let mut board = Board::default();
+    board.place_piece(Piece::new_tavern(Team::White), (5, 5).into());
+    board.place_piece(Piece::new_stable(Team::Black), (0, 0).into());
+    board.place_piece(Piece::new_inn(Team::White), (8, 8).into());
+    board.place_piece(Piece::new_cathedral(), (2, 2).into());
+    let kinds = |board: &Board| {
+      board
+        .pieces_in_placement_order()
+        .map(Piece::kind)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(kinds(&board), [
+      PieceKind::Tavern,
+      PieceKind::Stable,
+      PieceKind::Inn,
+      PieceKind::Cathedral
+    ]);
+
+    board.remove_piece((0, 0).into());
+    board.place_piece(Piece::new_stable(Team::Black), (0, 0).into());
+    assert_eq!(kinds(&board), [
+      PieceKind::Tavern,
+      PieceKind::Inn,
+      PieceKind::Cathedral,
+      PieceKind::Stable
+    ]);
+  }
+
+  #[test]
+  fn test_pieces_iter() {
+    let mut board = Board::default();
+    let ids = [
+      board.place_piece(Piece::new_tavern(Team::White), (5, 5).into()),
+      board.place_piece(Piece::new_stable(Team::Black), (0, 0).into()),
+      board.place_piece(Piece::new_inn(Team::White), (8, 8).into()),
+    ]
+    .map(|outcome| outcome.placed_id.expect("must place a piece"));
+    assert_eq!(
+      board
+        .pieces_iter()
+        .map(|(id, piece)| (id, piece.kind()))
+        .collect::<Vec<_>>(),
+      [
+        (ids[0], PieceKind::Tavern),
+        (ids[1], PieceKind::Stable),
+        (ids[2], PieceKind::Inn)
+      ]
+    );
+    assert_eq!(
+      board
+        .team_pieces_iter(Team::White)
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>(),
+      [ids[0], ids[2]]
+    );
+    assert_eq!(board.team_pieces_iter(Team::None).count(), 0);
+  }
+
+  #[test]
+  fn test_remove_cathedral() {
+    let mut board = Board::default();
+    assert_eq!(board.cathedral_status(), CathedralStatus::InBox);
+    assert_eq!(
+      board.remove_cathedral().expect_err("must be error"),
+      BoardError::PieceNotOnBoard
+    );
+
+    board.place_piece(Piece::new_tavern(Team::White), (0, 1).into());
+    board.place_piece(Piece::new_cathedral(), (3, 4).into());
+    assert_eq!(
+      board.cathedral_status(),
+      CathedralStatus::Placed((3, 4).into())
+    );
+
+    let cathedral = board.remove_cathedral().expect("must be on board");
+    assert_eq!(cathedral.kind(), PieceKind::Cathedral);
+    assert_eq!(board.cathedral_status(), CathedralStatus::InBox);
+    assert_eq!(board.pieces.len(), 1);
+    assert!(board
+      .tiles
+      .iter()
+      .all(|t| matches!(t, Tile::Empty(_) | Tile::Occupied(Team::White))));
+  }
+
+  #[test]
+  fn test_footprints() {
+    let mut board = Board::default();
+    assert!(board.footprint(Team::White).is_empty());
+    assert!(board.contiguous_footprints(Team::White).is_empty());
+
+    board.place_piece(Piece::new_stable(Team::White), (0, 0).into());
+    board.place_piece(Piece::new_tavern(Team::White), (0, 1).into());
+    board.place_piece(Piece::new_tavern(Team::White), (2, 1).into());
+    board.place_piece(Piece::new_inn(Team::White), (5, 5).into());
+    board.place_piece(Piece::new_bridge(Team::Black), (0, 2).into());
+    board.place_piece(Piece::new_cathedral(), (6, 0).into());
+
+    assert_eq!(board.footprint(Team::White).len(), 7);
+    assert_eq!(board.footprint(Team::Black).len(), 3);
+    assert_eq!(board.footprint(Team::None).len(), 6);
+    assert_eq!(board.contiguous_footprints(Team::White), [
+      HashSet::from([(0, 0), (1, 0), (0, 1)].map(Position::from)),
+      HashSet::from([(2, 1)].map(Position::from)),
+      HashSet::from([(5, 5), (5, 6), (6, 5)].map(Position::from)),
+    ]);
+  }
+
+  #[test]
+  fn test_territory() {
+    let board = BoardBuilder::new(
+      "
+      . W . . B .
+      W W . . B B
+      . . . . . .
+      . w . . . .
+      . . . . . .
+      . . . . . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert_eq!(
+      board.territory(Team::White),
+      HashSet::from([(0, 0), (3, 1)].map(Position::from))
+    );
+    assert_eq!(board.territory(Team::Black), HashSet::from([(0, 5).into()]));
+    assert!(board.territory(Team::Red).is_empty());
+    assert!(board.territory(Team::None).is_empty());
+
+    let board = BoardBuilder::new(
+      "
+      B W . .
+      W W . .
+      . . . .
+      . . . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert!(board.territory(Team::White).is_empty());
+  }
+
+  #[test]
+  fn test_threatened_pieces() {
+    let board = BoardBuilder::new(
+      "
+      B1 .  W1 . . .
+      W2 W2 .  . . .
+      .  .  .  . . .
+      .  B2 .  . . W3
+      .  .  .  . . .
+      .  .  .  . . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let id_at = |x, y| board.piece_id_at((x, y).into()).expect("must be there");
+    assert_eq!(board.threatened_pieces(Team::Black), [(
+      id_at(0, 0),
+      Move::Place {
+        team: Team::White,
+        kind: PieceKind::Stable,
+        rotation: Rotation::UP,
+        position: (1, 2).into(),
+      }
+    )]);
+    assert_eq!(board.threatened_pieces(Team::White), [(
+      id_at(3, 5),
+      Move::Place {
+        team: Team::Black,
+        kind: PieceKind::Castle,
+        rotation: Rotation::LEFT,
+        position: (2, 4).into(),
+      }
+    )]);
+    let mut castle = Piece::new_castle(Team::Black);
+    castle.rotate_to(Rotation::LEFT);
+    let outcome = board
+      .preview_placement(&castle, (2, 4).into())
+      .expect("must be legal");
+    assert_eq!(outcome.captured_pieces, [Piece::new_tavern(Team::White)]);
+    assert!(board.threatened_pieces(Team::Red).is_empty());
+    assert!(board.threatened_pieces(Team::None).is_empty());
+  }
+
+  #[test]
+  fn test_capture_in_corner() {
+    Scenario::given(
+      "
+      B . . .
+      . . . .
+      . . . .
+      . . . .
+      ",
+    )
+    .when("W:TAVERN@b1^U")
+    .then_captured(&[])
+    .run();
+
+    let board = Scenario::given(
+      "
+      B W . .
+      . . . .
+      . . . .
+      . . . .
+      ",
+    )
+    .when("W:TAVERN@a2^U")
+    .then_captured(&[PieceKind::Tavern])
+    .then_claimed(&[(0, 0)])
+    .then_tiles(
+      "
+      w W . .
+      W . . .
+      . . . .
+      . . . .
+      ",
+    )
+    .run();
+    assert_eq!(board.pieces.len(), 2);
+  }
+
+  #[test]
+  fn test_score_for() {
+    let mut board = Board::default();
+    assert_eq!(board.score_for(Team::White), Score {
+      unplaced_squares: SQUARES_PER_TEAM,
+      territory: 0,
+      captured_pieces: 0,
+    });
+
+    board.place_piece(Piece::new_tavern(Team::Black), (0, 0).into());
+    board.place_piece(Piece::new_tavern(Team::White), (0, 1).into());
+    board.place_piece(Piece::new_tavern(Team::White), (1, 0).into());
+    assert_eq!(board.score_for(Team::White), Score {
+      unplaced_squares: SQUARES_PER_TEAM - 2,
+      territory: 1,
+      captured_pieces: 1,
+    });
+    let score = board.score_for(Team::White);
+    assert_eq!(
+      score.penalty(Scoring::Unplaced),
+      SQUARES_PER_TEAM as isize - 2
+    );
+    assert_eq!(
+      score.penalty(Scoring::WeightedTerritory { weight: 3 }),
+      SQUARES_PER_TEAM as isize - 5
+    );
+    assert_eq!(board.captured_by(Team::White), [PieceKind::Tavern]);
+    assert!(board.captured_by(Team::Black).is_empty());
+    assert_eq!(board.score_for(Team::Black), Score {
+      unplaced_squares: SQUARES_PER_TEAM,
+      territory: 0,
+      captured_pieces: 0,
+    });
+
+    let score = board.score_in(Team::White, PieceSetPreset::Mini, 2);
+    assert_eq!(score.unplaced_squares, 15 - 2);
+    assert_eq!(score.territory, 1);
+    let red = board.score_in(Team::Red, PieceSetPreset::Standard, 3);
+    let reduced =
+      PieceKind::for_team_in(Team::Red, PieceSetPreset::Standard, 3)
+        .iter()
+        .map(|kind| kind.square_count())
+        .sum::<usize>();
+    assert_eq!(red.unplaced_squares, reduced);
+    assert!(reduced < SQUARES_PER_TEAM);
+  }
+
+  #[test]
+  fn test_capture_in_ring() {
+    Scenario::given(
+      "
+      B . . . . .
+      . W W W . .
+      . W B W . .
+      . . . . . .
+      . . . . . .
+      . . . . . .
+      ",
+    )
+    .when("W:CASTLE@b4^D")
+    .then_captured(&[PieceKind::Tavern])
+    .then_claimed(&[(2, 2), (3, 2)])
+    .then_tiles(
+      "
+      B . . . . .
+      . W1 W1 W1 . .
+      . W1 w  W1 . .
+      . W2 w  W2 . .
+      . W2 W2 W2 . .
+      . . . . . .
+      ",
+    )
+    .run();
+  }
+
+  #[test]
+  fn test_preview_placement() -> Result<(), BoardError> {
+    let mut board = Board::default();
+    board.place_piece(Piece::new_tavern(Team::Black), (5, 5).into());
+    board.place_piece(Piece::new_castle(Team::White), (4, 4).into());
+    let mut castle = Piece::new_castle(Team::White);
+    castle.rotate_clockwise();
+    castle.rotate_clockwise();
+
+    let preview = board.preview_placement(&castle, (6, 4).into())?;
+    assert_eq!(preview.captured_pieces, [Piece::new_tavern(Team::Black)]);
+    assert_eq!(
+      preview.claimed_tiles,
+      HashSet::from([(5, 5).into(), (6, 5).into()])
+    );
+    assert_eq!(board.tiles[(5, 5)], Tile::Occupied(Team::Black));
+    assert_eq!(board.pieces.len(), 2);
+
+    assert_eq!(board.try_place_piece(castle, (6, 4).into())?, preview);
+    assert_eq!(
+      board.preview_placement(&Piece::new_tavern(Team::Black), (5, 5).into()),
+      Err(BoardError::PieceOnEnemyTile((5, 5).into()))
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_piece_ids() -> Result<(), BoardError> {
+    let mut board = Board::with_size(4);
+    let black = board
+      .place_piece(Piece::new_tavern(Team::Black), (0, 0).into())
+      .placed_id
+      .expect("must place a piece");
+    assert_eq!(board.piece_id_at((0, 0).into()), Some(black));
+    assert_eq!(board.piece_by_id(black).map(Piece::team), Some(Team::Black));
+
+    let wall = board.place_piece(Piece::new_tavern(Team::White), (0, 1).into());
+    let white = wall.placed_id.expect("must place a piece");
+    assert_ne!(white, black);
+    assert!(white > black);
+    let expected = board
+      .preview_placement(&Piece::new_tavern(Team::White), (1, 0).into())?;
+    let (outcome, undo) = board.try_place_piece_undoable(
+      Piece::new_tavern(Team::White),
+      (1, 0).into(),
+    )?;
+    assert_eq!(outcome, expected);
+    assert_eq!(outcome.captured_ids, [black]);
+    assert_eq!(board.piece_by_id(black), None);
+    board.undo_placement(undo);
+    assert_eq!(board.piece_id_at((0, 0).into()), Some(black));
+    let redone =
+      board.place_piece(Piece::new_tavern(Team::White), (1, 0).into());
+    assert_eq!(redone.placed_id, outcome.placed_id);
+
+    let moved =
+      board.try_move_piece((0, 1).into(), (3, 3).into(), Rotation::UP)?;
+    assert_eq!(moved.placed_id, Some(white));
+    assert_eq!(board.piece_id_at((3, 3).into()), Some(white));
+    assert_eq!(
+      board.remove_by_id(white),
+      Ok(Piece::new_tavern(Team::White))
+    );
+    assert_eq!(board.remove_by_id(white), Err(BoardError::PieceNotOnBoard));
+    Ok(())
+  }
+
+  #[test]
+  fn test_undo_placement() -> Result<(), BoardError> {
+    let mut board = BoardBuilder::new(
+      "
+      b  b  B1 B1 B1 . .
+      b  B2 C  .  B3 . .
+      B4 C  C  C  B3 . .
+      B4 .  C  B5 .  . .
+      B4 .  C  B5 .  . .
+      .  .  B6 B5 .  . .
+      .  .  .  .  .  . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let tiles = board.tiles.clone();
+    let pieces = board.pieces.clone();
+    let placement_order = board.placement_order.clone();
+    let score = board.score_for(Team::Black);
+
+    let (outcome, undo) = board.try_place_piece_undoable(
+      Piece::new_tavern(Team::Black),
+      (4, 1).into(),
+    )?;
+    assert!(outcome.cathedral_captured);
+    board.undo_placement(undo);
+    assert_eq!(board.tiles, tiles);
+    assert_eq!(board.pieces, pieces);
+    assert_eq!(board.placement_order, placement_order);
+    assert_eq!(board.score_for(Team::Black), score);
+    assert_eq!(
+      board.cathedral_status(),
+      CathedralStatus::Placed((1, 1).into())
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_no_capture_of_two_pieces() {
+    let board = Scenario::given(
+      "
+      B . .  . . .
+      . W W  W . .
+      . W B1 W . .
+      . . B2 . . .
+      . . .  . . .
+      . . .  . . .
+      ",
+    )
+    .when("W:CASTLE@b4^D")
+    .then_captured(&[])
+    .then_claimed(&[])
+    .run();
+    assert_eq!(board.tiles[(2, 2)], Tile::Occupied(Team::Black));
+    assert_eq!(board.tiles[(3, 2)], Tile::Occupied(Team::Black));
+    assert_eq!(board.pieces.len(), 5);
+  }
+
+  #[test]
+  fn test_no_capture_without_enclosure() {
+    // The piece touches black tavern but leaves a gap below it.
+    Scenario::given(
+      "
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . W . .
+      . . . . W B W .
+      . . . . . . . .
+      . . . . . . . .
+      ",
+    )
+    .when("W:STABLE@f8^R")
+    .then_captured(&[])
+    .then_claimed(&[])
+    .run();
+
+    // Own pieces are never captured.
+    let board = Scenario::given(
+      "
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . . . .
+      . . . . . W . .
+      . . . . W B W .
+      . . . . . . . .
+      . . . . . W W .
+      ",
+    )
+    .when("W:TAVERN@f7^U")
+    .then_captured(&[PieceKind::Tavern])
+    .then_claimed(&[(5, 5)])
+    .run();
+    assert_eq!(board.pieces.len(), 5);
+  }
+
+  #[test]
+  fn test_claimed_tiles() {
+    let mut board = Scenario::given(
+      "
+      . W . .
+      . . . .
+      . . . .
+      . . . .
+      ",
+    )
+    .when("W:TAVERN@a2^U")
+    .then_captured(&[])
+    .then_claimed(&[(0, 0)])
+    .run();
+    assert_eq!(board.tiles[(0, 0)], Tile::Empty(Team::White));
+
+    let black_tavern = Piece::new_tavern(Team::Black);
+    assert_eq!(
+      board.can_place_piece(&black_tavern, (0, 0).into()),
+      Err(BoardError::PieceOnEnemyTile((0, 0).into()))
+    );
+    assert!(!board.quick_reject(&black_tavern, (0, 0).into()));
+    let white_tavern = Piece::new_tavern(Team::White);
+    assert_eq!(board.can_place_piece(&white_tavern, (0, 0).into()), Ok(()));
+    assert!(board
+      .place_piece(white_tavern, (0, 0).into())
+      .captured_pieces
+      .is_empty());
+  }
+
+  #[test]
+  fn test_cathedral_capture() {
+    // Black walls the cathedral off after capturing white tavern in the
+    // corner.
+    let mut board = Scenario::given(
+      "
+      b  b  B1 B1 B1 . .
+      b  B2 C  .  B3 . .
+      B4 C  C  C  B3 . .
+      B4 .  C  B5 .  . .
+      B4 .  C  B5 .  . .
+      .  .  B6 B5 .  . .
+      .  .  .  .  .  . .
+      ",
+    )
+    .when("B:TAVERN@b5^U")
+    .then_captured(&[PieceKind::Cathedral])
+    .then_claimed(&[
+      (1, 2),
+      (1, 3),
+      (2, 1),
+      (2, 2),
+      (2, 3),
+      (3, 1),
+      (3, 2),
+      (4, 2),
+    ])
+    .then_tiles(
+      "
+      b  b  B1 B1 B1 . .
+      b  B2 b  b  B3 . .
+      B4 b  b  b  B3 . .
+      B4 b  b  B5 .  . .
+      B4 B7 b  B5 .  . .
+      .  .  B6 B5 .  . .
+      .  .  .  .  .  . .
+      ",
+    )
+    .run();
+    assert_eq!(board.cathedral_status(), CathedralStatus::Captured);
+
+    // Once captured, the cathedral can't come back.
+    assert_eq!(
+      board.can_place_piece(&Piece::new_cathedral(), (3, 4).into()),
+      Err(BoardError::CathedralOutOfPlay)
+    );
+    assert_eq!(
+      board.remove_cathedral().expect_err("must be error"),
+      BoardError::PieceNotOnBoard
+    );
+    assert_eq!(board.cathedral_status(), CathedralStatus::Captured);
+  }
+
+  #[test]
+  fn test_rule_toggles() {
+    let corner = "
+      B W . .
+      . . . .
+      . . . .
+      . . . .
+      ";
+    Scenario::given(corner)
+      .rules(Rules {
+        capture_occupied_regions: false,
+        ..Rules::default()
+      })
+      .when("W:TAVERN@a2^U")
+      .then_captured(&[])
+      .then_claimed(&[])
+      .run();
+    Scenario::given(&corner.replace('B', "."))
+      .rules(Rules {
+        capture_occupied_regions: false,
+        ..Rules::default()
+      })
+      .when("W:TAVERN@a2^U")
+      .then_claimed(&[(0, 0)])
+      .run();
+
+    Scenario::given(
+      "
+      b  b  B1 B1 B1 . .
+      b  B2 C  .  B3 . .
+      B4 C  C  C  B3 . .
+      B4 .  C  B5 .  . .
+      B4 .  C  B5 .  . .
+      .  .  B6 B5 .  . .
+      .  .  .  .  .  . .
+      ",
+    )
+    .rules(Rules {
+      cathedral_capturable: false,
+      ..Rules::default()
+    })
+    .when("B:TAVERN@b5^U")
+    .then_captured(&[])
+    .then_claimed(&[])
+    .run();
+
+    let board = BoardBuilder::new(
+      "
+      w W .
+      W . .
+      . . .
+      ",
+    )
+    .rules(Rules {
+      claimed_ground_blocks: false,
+      ..Rules::default()
+    })
+    .build()
+    .expect("must be valid art");
+    assert_eq!(
+      board.can_place_piece(&Piece::new_tavern(Team::Black), (0, 0).into()),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn test_cathedral_does_not_capture() {
+    Scenario::given(
+      "
+      W . . . .
+      . . . . .
+      . . . . .
+      . . . . .
+      . . . . .
+      ",
+    )
+    .when("N:CATHEDRAL@b1^U")
+    .then_captured(&[])
+    .then_claimed(&[])
+    .run();
+  }
+
+  #[test]
+  fn test_capture_behind_wall() {
+    // A wall from edge to edge cuts the board in two, the smaller part is
+    // enclosed.
+    Scenario::given(
+      "
+      B . W . .
+      . . W . .
+      . . W . .
+      . . . . .
+      . . . . B
+      ",
+    )
+    .when("W:STABLE@a3^R")
+    .then_captured(&[PieceKind::Tavern])
+    .then_claimed(&[(0, 0), (0, 1), (1, 0), (1, 1)])
+    .then_tiles(
+      "
+      w  w  W1 . .
+      w  w  W1 . .
+      W2 W2 W1 . .
+      .  .  .  . .
+      .  .  .  . B
+      ",
+    )
+    .run();
+  }
+
+  #[test]
+  fn test_scatter_ruins() {
+    let blocked = |board: &Board| {
+      board
+        .tiles
+        .indexed_iter()
+        .filter(|(_, t)| **t == Tile::Blocked)
+        .map(|(p, _)| Position::from(p))
+        .collect::<HashSet<_>>()
+    };
+    let mut board = Board::default();
+    board.scatter_ruins(7, 5);
+    let ruins = blocked(&board);
+    assert_eq!(ruins.len(), 5);
+
+    let mut same_seed = Board::default();
+    same_seed.scatter_ruins(7, 5);
+    assert_eq!(blocked(&same_seed), ruins);
+
+    let mut small = Board::with_size(2);
+    small.place_piece(Piece::new_tavern(Team::White), (0, 0).into());
+    small.scatter_ruins(1, 10);
+    assert_eq!(blocked(&small).len(), 3);
+    assert_eq!(small.tiles[(0, 0)], Tile::Occupied(Team::White));
+  }
+
+  #[test]
+  fn test_blocked_tiles() {
+    let mut board = Board::default();
+    board.set_tiles_for_test(&[(0, 0)], Tile::Blocked);
+    let tavern = Piece::new_tavern(Team::White);
+    assert!(board.quick_reject(&tavern, (0, 0).into()));
+    assert_eq!(
+      board.can_place_piece(&tavern, (0, 0).into()),
+      Err(BoardError::PieceOnBlockedTile((0, 0).into()))
+    );
+    let region = HashSet::from([(0, 0).into(), (0, 1).into()]);
+    assert_eq!(board.legal_placements_in(&tavern, &region).len(), 1);
+
+    // Ruins wall off tiles like board edges do, but are never captured.
+    board.set_tiles_for_test(&[(0, 2)], Tile::Blocked);
+    board.place_piece(Piece::new_tavern(Team::Black), (0, 1).into());
+    let outcome =
+      board.place_piece(Piece::new_tavern(Team::White), (1, 1).into());
+    assert_eq!(outcome.captured_pieces, [Piece::new_tavern(Team::Black)]);
+    assert_eq!(board.tiles[(0, 0)], Tile::Blocked);
+    assert_eq!(board.tiles[(0, 2)], Tile::Blocked);
+  }
+
   #[test]
   fn test_find_tile_sets() {
     let mut board = Board::default();
     let piece = Piece::new_inn(Team::White);
-    board.place_piece(piece.clone(), (8, 8).into());
+    board.insert_piece(piece.clone().placed_at((8, 8).into()));
     let piece = piece.placed_at((8, 8).into());
 
     let tile_sets = board.find_tile_sets(&piece);
@@ -452,4 +2617,82 @@ mod tests {
       [96, 1].into()
     );
   }
+
+  #[test]
+  fn test_find_tile_sets_diagonal_wall() {
+    // Taverns touching by corners cut off the corner tile:
+    // <pre>
+    //   []
+    // []
+    // </pre>
+    let rules = |region_connectivity| Rules {
+      region_connectivity,
+      ..Rules::default()
+    };
+    let tavern = Piece::new_tavern(Team::White);
+
+    let mut board =
+      Board::with_size_and_rules(10, rules(Connectivity::Orthogonal));
+    board.insert_piece(tavern.clone().placed_at((0, 1).into()));
+    board.insert_piece(tavern.clone().placed_at((1, 0).into()));
+    let tile_sets =
+      board.find_tile_sets(&tavern.clone().placed_at((1, 0).into()));
+    assert_eq!(
+      tile_sets.iter().map(HashSet::len).collect::<HashSet<_>>(),
+      [97, 1].into()
+    );
+
+    let mut board =
+      Board::with_size_and_rules(10, rules(Connectivity::Diagonal));
+    board.insert_piece(tavern.clone().placed_at((0, 1).into()));
+    board.insert_piece(tavern.clone().placed_at((1, 0).into()));
+    let tile_sets = board.find_tile_sets(&tavern.placed_at((1, 0).into()));
+    assert_eq!(tile_sets.len(), 1);
+    assert_eq!(tile_sets[0].len(), 98);
+  }
+
+  #[test]
+  fn test_find_tile_sets_diagonal_ring() {
+    // A diamond of taverns around (2, 2) encloses it only if corners count as
+    // walls:
+    // <pre>
+    //   []
+    // []  []
+    //   []
+    // </pre>
+    let rules = |region_connectivity| Rules {
+      region_connectivity,
+      ..Rules::default()
+    };
+    let tavern = Piece::new_tavern(Team::White);
+    for connectivity in [Connectivity::Orthogonal, Connectivity::Diagonal] {
+      let mut board = Board::with_size_and_rules(10, rules(connectivity));
+      for p in [(1, 2), (2, 1), (2, 3), (3, 2)] {
+        board.insert_piece(tavern.clone().placed_at(p.into()));
+      }
+      let tile_sets =
+        board.find_tile_sets(&tavern.clone().placed_at((3, 2).into()));
+      let sizes = tile_sets.iter().map(HashSet::len).collect::<HashSet<_>>();
+      match connectivity {
+        Connectivity::Orthogonal => assert_eq!(sizes, [95, 1].into()),
+        Connectivity::Diagonal => assert_eq!(sizes, [96].into()),
+      }
+    }
+
+    // A square ring encloses its center under both rules.
+    for connectivity in [Connectivity::Orthogonal, Connectivity::Diagonal] {
+      let mut board = Board::with_size_and_rules(10, rules(connectivity));
+      board
+        .insert_piece(Piece::new_castle(Team::White).placed_at((4, 4).into()));
+      let mut castle = Piece::new_castle(Team::White);
+      castle.rotate_clockwise();
+      castle.rotate_clockwise();
+      board.insert_piece(castle.clone().placed_at((6, 4).into()));
+      let tile_sets = board.find_tile_sets(&castle.placed_at((6, 4).into()));
+      assert_eq!(
+        tile_sets.iter().map(HashSet::len).collect::<HashSet<_>>(),
+        [88, 2].into()
+      );
+    }
+  }
 }
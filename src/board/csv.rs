@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use super::{Board, Tile};
+use crate::{error::ParseBoardError, position::Position, rules::Rules, Team};
+
+impl Board {
+  /// Exports board as comma-separated values, one line per row of tiles.
+  ///
+  /// Each cell holds tile's state: an empty string for a neutral empty tile,
+  /// `w`, `b`, `r` or `g` for an empty tile claimed by white, black, red or
+  /// green team, `X` for a blocked tile, and `W`, `B`, `R`, `G` or `C`
+  /// followed by piece id for a tile occupied by a piece of one of the teams
+  /// or the cathedral. Piece ids number pieces in placement order, which
+  /// the importer restores.
+  pub fn to_csv(&self) -> String {
+    self.to_delimited(',')
+  }
+
+  /// Same as `to_csv` but with tab-separated values.
+  pub fn to_tsv(&self) -> String {
+    self.to_delimited('\t')
+  }
+
+  /// Imports board from comma-separated values produced by `to_csv`.
+  /// The board gets default rules.
+  pub fn from_csv(csv: &str) -> Result<Self, ParseBoardError> {
+    Self::from_delimited(csv, ',')
+  }
+
+  /// Imports board from tab-separated values produced by `to_tsv`.
+  /// The board gets default rules.
+  pub fn from_tsv(tsv: &str) -> Result<Self, ParseBoardError> {
+    Self::from_delimited(tsv, '\t')
+  }
+
+  fn to_delimited(&self, delimiter: char) -> String {
+    let mut piece_ids = HashMap::new();
+    for (id, piece) in self.pieces_in_placement_order().enumerate() {
+      for p in piece.occupied_positions_iter() {
+        piece_ids.insert(p, id);
+      }
+    }
+
+    let mut out = String::new();
+    for (x, row) in self.tiles.rows().into_iter().enumerate() {
+      let cells = row
+        .iter()
+        .enumerate()
+        .map(|(y, tile)| match tile {
+          Tile::Empty(team) => match team {
+            Team::White => "w".to_owned(),
+            Team::Black => "b".to_owned(),
+            Team::Red => "r".to_owned(),
+            Team::Green => "g".to_owned(),
+            Team::None => String::new(),
+          },
+          Tile::Occupied(team) => {
+            let id = piece_ids[&Position { x, y }];
+            match team {
+              Team::White => format!("W{id}"),
+              Team::Black => format!("B{id}"),
+              Team::Red => format!("R{id}"),
+              Team::Green => format!("G{id}"),
+              Team::None => format!("C{id}"),
+            }
+          }
+          Tile::Blocked => "X".to_owned(),
+        })
+        .collect::<Vec<_>>();
+      out.push_str(&cells.join(&delimiter.to_string()));
+      out.push('\n');
+    }
+    out
+  }
+
+  fn from_delimited(
+    text: &str,
+    delimiter: char,
+  ) -> Result<Self, ParseBoardError> {
+    let rows = text
+      .lines()
+      .map(|line| line.split(delimiter).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let size = rows.len();
+    if rows.iter().any(|row| row.len() != size) {
+      return Err(ParseBoardError::NotSquare);
+    }
+
+    let mut board = Self::with_size_and_rules(size, Rules::default());
+    let mut pieces = HashMap::<usize, (Team, Vec<Position>)>::new();
+    for (x, row) in rows.iter().enumerate() {
+      for (y, cell) in row.iter().enumerate() {
+        let position = Position { x, y };
+        let invalid_tile =
+          || ParseBoardError::InvalidTile(cell.to_string(), position);
+        if cell.is_empty() {
+          continue;
+        }
+        if *cell == "X" {
+          board.tiles[(x, y)] = Tile::Blocked;
+          continue;
+        }
+        let team = match cell.get(..1) {
+          Some("w" | "W") => Team::White,
+          Some("b" | "B") => Team::Black,
+          Some("r" | "R") => Team::Red,
+          Some("g" | "G") => Team::Green,
+          Some("C") => Team::None,
+          _ => return Err(invalid_tile()),
+        };
+        let id = &cell[1..];
+        if cell.starts_with(|c: char| c.is_ascii_lowercase()) {
+          if !id.is_empty() {
+            return Err(invalid_tile());
+          }
+          board.tiles[(x, y)] = Tile::Empty(team);
+          continue;
+        }
+        let id = match id.parse::<usize>() {
+          Ok(n) if id.bytes().all(|b| b.is_ascii_digit()) => n,
+          _ => return Err(invalid_tile()),
+        };
+        let (piece_team, positions) =
+          pieces.entry(id).or_insert((team, Vec::new()));
+        if *piece_team != team {
+          return Err(ParseBoardError::InvalidPiece(id));
+        }
+        positions.push(position);
+      }
+    }
+
+    let mut pieces = pieces.into_iter().collect::<Vec<_>>();
+    pieces.sort_by_key(|(id, _)| *id);
+    for (id, (team, positions)) in pieces {
+      board
+        .insert_piece_covering(team, &positions)
+        .ok_or(ParseBoardError::InvalidPiece(id))?;
+    }
+    Ok(board)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    board::PieceId,
+    piece::{Piece, Released},
+  };
+
+  #[test]
+  fn test_csv_round_trip() {
+    let mut board = Board::with_size(4);
+    let mut inn = Piece::new_inn(Team::White);
+    inn.rotate_clockwise();
+    board.place_piece(inn, (0, 0).into());
+    board.place_piece(Piece::new_abbey(Team::Black), (2, 1).into());
+    board.tiles[(3, 0)] = Tile::Empty(Team::Black);
+    board.tiles[(1, 3)] = Tile::Blocked;
+
+    let csv = board.to_csv();
+    assert_eq!(csv, "W0,W0,,\n,W0,,X\n,B1,B1,\nb,,B1,B1\n");
+
+    let mut reordered = Board::with_size(4);
+    reordered.place_piece(Piece::new_abbey(Team::Black), (2, 1).into());
+    let mut inn = Piece::new_inn(Team::White);
+    inn.rotate_clockwise();
+    reordered.place_piece(inn, (0, 0).into());
+    let reordered_csv = reordered.to_csv();
+    assert_eq!(reordered_csv, "W1,W1,,\n,W1,,\n,B0,B0,\n,,B0,B0\n");
+    let imported = Board::from_csv(&reordered_csv).expect("must be valid csv");
+    assert_eq!(
+      imported
+        .pieces_in_placement_order()
+        .map(Piece::team)
+        .collect::<Vec<_>>(),
+      [Team::Black, Team::White]
+    );
+
+    let imported = Board::from_csv(&csv).expect("must be valid csv");
+    assert_eq!(imported.tiles, board.tiles);
+    assert_eq!(imported.to_csv(), csv);
+    let pieces = |board: &Board| {
+      let mut pieces = board
+        .pieces
+        .iter()
+        .map(|(id, piece)| (*id, piece.clone().released()))
+        .collect::<Vec<(PieceId, Piece<Released>)>>();
+      pieces.sort_by_key(|(id, _)| *id);
+      pieces
+    };
+    assert_eq!(pieces(&imported), pieces(&board));
+
+    let tsv = board.to_tsv();
+    assert_eq!(tsv, csv.replace(',', "\t"));
+    assert_eq!(
+      Board::from_tsv(&tsv).expect("must be valid tsv").tiles,
+      board.tiles
+    );
+  }
+
+  #[test]
+  fn test_csv_cathedral() {
+    let mut board = Board::default();
+    board.place_piece(Piece::new_cathedral(), (3, 3).into());
+    let imported = Board::from_csv(&board.to_csv()).expect("must be valid csv");
+    assert_eq!(imported.tiles, board.tiles);
+    assert_eq!(imported.pieces.len(), 1);
+  }
+
+  #[test]
+  fn test_csv_multiplayer_teams() {
+    let mut board = Board::with_size(3);
+    board.place_piece(Piece::new_abbey(Team::Green), (0, 0).into());
+    board.place_piece(Piece::new_tavern(Team::Red), (2, 2).into());
+    board.tiles[(2, 0)] = Tile::Empty(Team::Red);
+    let csv = board.to_csv();
+    assert_eq!(csv, "G0,G0,g\n,G0,G0\nr,,R1\n");
+    let imported = Board::from_csv(&csv).expect("must be valid csv");
+    assert_eq!(imported.tiles, board.tiles);
+  }
+
+  #[test]
+  fn test_csv_errors() {
+    assert_eq!(
+      Board::from_csv(",\n,\n,\n").err(),
+      Some(ParseBoardError::NotSquare)
+    );
+    assert_eq!(
+      Board::from_csv("Y,\n,\n").err(),
+      Some(ParseBoardError::InvalidTile("Y".into(), (0, 0).into()))
+    );
+    assert_eq!(
+      Board::from_csv(",w1\n,\n").err(),
+      Some(ParseBoardError::InvalidTile("w1".into(), (0, 1).into()))
+    );
+    assert_eq!(
+      Board::from_csv(",W\n,\n").err(),
+      Some(ParseBoardError::InvalidTile("W".into(), (0, 1).into()))
+    );
+    assert_eq!(
+      Board::from_csv("W0,B0\n,\n").err(),
+      Some(ParseBoardError::InvalidPiece(0))
+    );
+    assert_eq!(
+      Board::from_csv("W0,\n,W0\n").err(),
+      Some(ParseBoardError::InvalidPiece(0))
+    );
+  }
+}
@@ -0,0 +1,153 @@
+use std::{collections::HashMap, mem};
+
+use ndarray::Array2;
+
+use super::{Board, PieceId, Tile};
+use crate::{
+  error::{BoardError, InvalidBoardError},
+  piece::{Piece, Placed, Released},
+  position::Position,
+};
+
+/// Edits a board bypassing the rules, so scenario and puzzle authors can set
+/// up positions that didn't come up in play. Edits apply right away, but
+/// unless `finish` finds the board consistent they are rolled back once the
+/// editor is gone.
+pub struct BoardEditor<'a> {
+  board: &'a mut Board,
+  tiles: Array2<Tile>,
+  pieces: HashMap<PieceId, Piece<Placed>>,
+  placement_order: Vec<PieceId>,
+  next_id: usize,
+  finished: bool,
+}
+
+impl Board {
+  /// Returns an editor of this board.
+  pub fn editor(&mut self) -> BoardEditor<'_> {
+    BoardEditor {
+      tiles: self.tiles.clone(),
+      pieces: self.pieces.clone(),
+      placement_order: self.placement_order.clone(),
+      next_id: self.next_id,
+      board: self,
+      finished: false,
+    }
+  }
+}
+
+impl BoardEditor<'_> {
+  /// Returns the board as it is with edits made so far.
+  pub fn board(&self) -> &Board {
+    self.board
+  }
+
+  /// Overwrites tile at given `position`, whatever is there.
+  pub fn set_tile(
+    &mut self,
+    position: Position,
+    tile: Tile,
+  ) -> Result<(), BoardError> {
+    let cell = self
+      .board
+      .tiles
+      .get_mut((position.x, position.y))
+      .ok_or(BoardError::TileOutOfBounds(position))?;
+    *cell = tile;
+    Ok(())
+  }
+
+  /// Puts `piece` on the board at given position, only checking that it
+  /// fits into the board. Nothing gets captured or claimed. Returns the id
+  /// the piece gets.
+  pub fn insert_piece(
+    &mut self,
+    piece: Piece<Released>,
+    position: Position,
+  ) -> Result<PieceId, BoardError> {
+    if let Some(p) = piece
+      .occupied_positions_iter(position)
+      .find(|p| self.board.get(*p).is_none())
+    {
+      return Err(BoardError::PieceOutOfBounds(p));
+    }
+    Ok(self.board.insert_piece(piece.placed_at(position)))
+  }
+
+  /// Removes piece with given `id`, leaving neutral empty tiles under it.
+  pub fn remove_piece(
+    &mut self,
+    id: PieceId,
+  ) -> Result<Piece<Released>, BoardError> {
+    self.board.remove_by_id(id)
+  }
+
+  /// Checks the edited board with `Board::validate`, keeping edits if it
+  /// passes and rolling them back otherwise.
+  pub fn finish(mut self) -> Result<(), InvalidBoardError> {
+    self.board.validate()?;
+    self.finished = true;
+    Ok(())
+  }
+}
+
+impl Drop for BoardEditor<'_> {
+  fn drop(&mut self) {
+    if self.finished {
+      return;
+    }
+    self.board.tiles.assign(&self.tiles);
+    self.board.pieces = mem::take(&mut self.pieces);
+    self.board.placement_order = mem::take(&mut self.placement_order);
+    self.board.next_id = self.next_id;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{piece::Rotation, Team};
+
+  #[test]
+  fn test_editor() -> Result<(), BoardError> {
+    let mut board = Board::with_size(4);
+    let mut editor = board.editor();
+    editor.insert_piece(Piece::new_stable(Team::White), (0, 1).into())?;
+    editor.set_tile((1, 0).into(), Tile::Occupied(Team::White))?;
+    editor.set_tile((0, 0).into(), Tile::Empty(Team::White))?;
+    assert_eq!(
+      editor.set_tile((4, 0).into(), Tile::Blocked),
+      Err(BoardError::TileOutOfBounds((4, 0).into()))
+    );
+    assert_eq!(editor.board().tile((0, 0).into()), Tile::Empty(Team::White));
+    assert_eq!(
+      editor.finish(),
+      Err(InvalidBoardError::TileMismatch((1, 0).into()))
+    );
+    assert!(board
+      .tiles_iter()
+      .all(|(_, t)| t == Tile::Empty(Team::None)));
+    assert_eq!(board.pieces_iter().count(), 0);
+
+    let mut editor = board.editor();
+    let mut inn = Piece::new_inn(Team::White);
+    inn.rotate_to(Rotation::DOWN);
+    assert_eq!(
+      editor.insert_piece(inn.clone(), (3, 3).into()),
+      Err(BoardError::PieceOutOfBounds((3, 4).into()))
+    );
+    let id = editor.insert_piece(inn, (0, 0).into())?;
+    editor.set_tile((0, 0).into(), Tile::Empty(Team::White))?;
+    editor.set_tile((3, 3).into(), Tile::Blocked)?;
+    assert_eq!(editor.finish(), Ok(()));
+    assert_eq!(board.tile((0, 0).into()), Tile::Empty(Team::White));
+    assert_eq!(board.tile((3, 3).into()), Tile::Blocked);
+    assert!(board.piece_by_id(id).is_some());
+
+    let mut editor = board.editor();
+    editor.remove_piece(id)?;
+    drop(editor);
+    assert!(board.piece_by_id(id).is_some());
+    Ok(())
+  }
+}
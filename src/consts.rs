@@ -0,0 +1,60 @@
+//! Parameters of the standard game.
+
+/// Side length of the standard square board.
+pub const STANDARD_BOARD_SIZE: usize = 10;
+
+/// Number of pieces each team starts with, not counting the cathedral.
+pub const PIECES_PER_TEAM: usize = 14;
+
+/// Number of tiles all pieces of a single team occupy together.
+pub const SQUARES_PER_TEAM: usize = 47;
+
+/// Number of tiles the cathedral occupies.
+pub const CATHEDRAL_SQUARES: usize = 6;
+
+/// Largest side of any piece's bounding box.
+pub const MAX_PIECE_DIMENSION: usize = 4;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{piece::Piece, Team};
+
+  #[test]
+  fn test_piece_set_constants() {
+    let team = Team::White;
+    let pieces = [
+      Piece::new_tavern(team),
+      Piece::new_tavern(team),
+      Piece::new_stable(team),
+      Piece::new_stable(team),
+      Piece::new_inn(team),
+      Piece::new_inn(team),
+      Piece::new_bridge(team),
+      Piece::new_square(team),
+      Piece::new_manor(team),
+      Piece::new_abbey(team),
+      Piece::new_academy(team),
+      Piece::new_infirmary(team),
+      Piece::new_castle(team),
+      Piece::new_tower(team),
+    ];
+    assert_eq!(pieces.len(), PIECES_PER_TEAM);
+    assert_eq!(
+      pieces
+        .iter()
+        .map(|p| p.kind().square_count())
+        .sum::<usize>(),
+      SQUARES_PER_TEAM
+    );
+
+    let cathedral = Piece::new_cathedral();
+    assert_eq!(cathedral.kind().square_count(), CATHEDRAL_SQUARES);
+    assert!(pieces
+      .iter()
+      .chain([&cathedral])
+      .map(Piece::size)
+      .all(|s| s.x.max(s.y) <= MAX_PIECE_DIMENSION));
+    assert_eq!(cathedral.size().x, MAX_PIECE_DIMENSION);
+  }
+}
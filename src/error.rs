@@ -1,8 +1,8 @@
 use thiserror::Error;
 
-use crate::position::Position;
+use crate::{board::PieceId, piece::PieceKind, position::Position, Team};
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
 pub enum BoardError {
   #[error("piece was placed out of bounds at {0:?}")]
   PieceOutOfBounds(Position),
@@ -10,6 +10,129 @@ pub enum BoardError {
   PieceOnOccupiedTile(Position),
   #[error("piece was placed on other team's tile")]
   PieceOnEnemyTile(Position),
+  #[error("piece was placed on blocked tile")]
+  PieceOnBlockedTile(Position),
+  #[error("tile {0:?} is out of bounds")]
+  TileOutOfBounds(Position),
   #[error("place doesn't belong to this board")]
   PieceNotOnBoard,
+  #[error("cathedral was captured and is out of play")]
+  CathedralOutOfPlay,
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum InvalidBoardError {
+  #[error("piece lies out of bounds at {0:?}")]
+  PieceOutOfBounds(Position),
+  #[error("pieces overlap at {0:?}")]
+  OverlappingPieces(Position),
+  #[error("piece has id {0:?} the board never gave out")]
+  UnknownId(PieceId),
+  #[error("placement order doesn't list every piece exactly once")]
+  PlacementOrder,
+  #[error("tile at {0:?} doesn't match pieces covering it")]
+  TileMismatch(Position),
+  #[error("claimed tile at {0:?} isn't walled off from neutral tiles")]
+  OpenClaim(Position),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseGameIdError {
+  #[error("game id must have `word-word-number` format")]
+  InvalidFormat,
+  #[error("game id words must consist of lowercase ascii letters")]
+  InvalidWord,
+  #[error("game id number must be in range 0..10000")]
+  InvalidNumber,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseBoardError {
+  #[error("board must have as many columns as rows")]
+  NotSquare,
+  #[error("invalid tile {0:?} at {1:?}")]
+  InvalidTile(String, Position),
+  #[error("tiles of piece {0} don't form a piece of a single team")]
+  InvalidPiece(usize),
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum GameError {
+  #[error("it is not {0:?} team's turn")]
+  NotYourTurn(Team),
+  #[error("{0:?} team doesn't take part in the game")]
+  NotPlaying(Team),
+  #[error("{0} is not in hand")]
+  PieceNotInHand(PieceKind),
+  #[error("cathedral must be placed first")]
+  CathedralNotPlaced,
+  #[error("cathedral has already been placed")]
+  CathedralAlreadyPlaced,
+  #[error("can't pass while there are legal placements")]
+  CannotPass,
+  #[error("game is already over")]
+  GameOver,
+  #[error("the other team hasn't offered a draw")]
+  NoDrawOffer,
+  #[error(transparent)]
+  Board(#[from] BoardError),
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum SetupError {
+  #[error("{0:?} team can't be the one to move")]
+  InvalidTurn(Team),
+  #[error("white team must move first while the cathedral is not placed")]
+  CathedralNotPlaced,
+  #[error("{0:?} team has more pieces of kind {1} than its set holds")]
+  TooManyPieces(Team, PieceKind),
+  #[error("{0:?} team doesn't take part in the game")]
+  NotPlaying(Team),
+  #[error("{0} is not in hand")]
+  PieceNotInHand(PieceKind),
+  #[error(transparent)]
+  Board(#[from] BoardError),
+  #[error(transparent)]
+  InvalidBoard(#[from] InvalidBoardError),
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+  #[error("move must have `team:PIECE@square^rotation` format")]
+  InvalidFormat,
+  #[error("move team must be one of `W`, `B`, `R`, `G` or `N`")]
+  InvalidTeam,
+  #[error("unknown piece {0:?}")]
+  InvalidPiece(String),
+  #[error("invalid square {0:?}")]
+  InvalidSquare(String),
+  #[error("move rotation must be one of `U`, `R`, `D` or `L`")]
+  InvalidRotation,
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+  #[error("move {index} can't be parsed: {error}")]
+  InvalidMove {
+    index: usize,
+    #[source]
+    error: ParseMoveError,
+  },
+  #[error("move {index} is illegal: {error}")]
+  IllegalMove {
+    index: usize,
+    #[source]
+    error: GameError,
+  },
+}
+
+#[cfg(feature = "unstable")]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BlitzError {
+  #[error("only white and black teams propose moves")]
+  InvalidTeam,
+  #[error("{0:?} team has already proposed a move this turn")]
+  AlreadyProposed(Team),
+  #[error("blitz is over")]
+  Over,
 }
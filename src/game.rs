@@ -0,0 +1,2367 @@
+use std::{
+  cmp::Reverse,
+  collections::{HashMap, HashSet},
+  fmt::Display,
+  marker::PhantomData,
+  str::FromStr,
+};
+
+use crate::{
+  board::{Board, CathedralStatus, PlacementOutcome, PlacementUndo, Score},
+  error::{GameError, ParseMoveError, ReplayError, SetupError},
+  piece::{Piece, PieceKind, Rotation},
+  piece_set::PieceSet,
+  position::Position,
+  rules::{GameConfig, PieceSetPreset, Rules, Scoring, TieBreak},
+  Team,
+};
+
+/// Stage of a game, telling what the next move must be.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Phase {
+  /// White team must place the cathedral before any team piece goes down.
+  Cathedral,
+  /// Teams take turns placing their pieces.
+  Pieces,
+}
+
+/// How a finished game ended.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameResult {
+  /// Given team has the lowest penalty, with the official scoring fewest
+  /// squares of pieces left in hand.
+  Winner(Team),
+  /// Teams share the lowest penalty.
+  Draw,
+  /// Given team resigned from a game of two teams and lost.
+  Resignation(Team),
+  /// Teams agreed to a draw.
+  Agreed,
+}
+
+impl GameResult {
+  /// Returns team that won, if any.
+  pub fn winner(self) -> Option<Team> {
+    match self {
+      Self::Winner(team) => Some(team),
+      Self::Resignation(team) => Some(team.opponent()),
+      Self::Draw | Self::Agreed => None,
+    }
+  }
+}
+
+/// Everything a team's score is made of, to show why a game ended the way
+/// it did.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScoreBreakdown {
+  pub team: Team,
+  /// Pieces left in hand with their square counts.
+  pub unplaced: Vec<(PieceKind, usize)>,
+  /// Total squares of pieces left in hand. The fewer, the better.
+  pub unplaced_squares: usize,
+  /// Kinds of pieces the team captured, in order of capture.
+  pub captured: Vec<PieceKind>,
+  /// Empty tiles the team has claimed.
+  pub territory: usize,
+  /// Way unplaced squares and territory add up to the penalty.
+  pub scoring: Scoring,
+  /// Penalty the team ended up with. The lower, the better.
+  pub penalty: isize,
+  /// Rule the game resolves equal penalties by.
+  pub tie_break: TieBreak,
+  /// Whether the team ran out of placements before the other team did.
+  pub passed_first: bool,
+}
+
+impl Display for ScoreBreakdown {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "Unplaced:")?;
+    if self.unplaced.is_empty() {
+      write!(f, " none")?;
+    }
+    for (i, (kind, squares)) in self.unplaced.iter().enumerate() {
+      let separator = if i == 0 { " " } else { ", " };
+      write!(f, "{separator}{kind} ({squares})")?;
+    }
+    writeln!(f, " = {} squares", self.unplaced_squares)?;
+    write!(f, "Captured:")?;
+    if self.captured.is_empty() {
+      write!(f, " none")?;
+    }
+    for (i, kind) in self.captured.iter().enumerate() {
+      let separator = if i == 0 { " " } else { ", " };
+      write!(f, "{separator}{kind}")?;
+    }
+    writeln!(f)?;
+    writeln!(f, "Territory: {} tiles", self.territory)?;
+    if let Scoring::WeightedTerritory { weight } = self.scoring {
+      writeln!(
+        f,
+        "Penalty: {} - {} x {weight} = {}",
+        self.unplaced_squares, self.territory, self.penalty
+      )?;
+    }
+    write!(f, "Tie break: {:?}", self.tie_break)?;
+    if self.passed_first {
+      write!(f, ", passed first")?;
+    }
+    Ok(())
+  }
+}
+
+/// A move a team can make.
+///
+/// Moves are ordered as variants are declared: placements first, then
+/// passes, resignations, draw offers and acceptances.
+/// Placements compare by team, kind, rotation and position in that order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Move {
+  /// Puts piece of `kind` from `team`'s hand on the board, rotated to
+  /// `rotation`, with its layout at `position`. The cathedral is placed by
+  /// white team with `kind` of `PieceKind::Cathedral`.
+  Place {
+    team: Team,
+    kind: PieceKind,
+    rotation: Rotation,
+    position: Position,
+  },
+  /// Skips `team`'s turn. Only allowed when none of its pieces in hand fit
+  /// anywhere on the board.
+  Pass { team: Team },
+  /// Gives the game up, making the other team the winner. A team can resign
+  /// at any moment, not only on its turn.
+  Resign { team: Team },
+  /// Offers other teams a draw. Like resigning, it can be done at any
+  /// moment and doesn't take a turn. The offer lapses once another team
+  /// places a piece or passes instead of accepting.
+  OfferDraw { team: Team },
+  /// Accepts another team's draw offer, ending the game in a draw.
+  AcceptDraw { team: Team },
+}
+
+impl Move {
+  /// Returns team making the move.
+  pub fn team(&self) -> Team {
+    match *self {
+      Self::Place { team, .. }
+      | Self::Pass { team }
+      | Self::Resign { team }
+      | Self::OfferDraw { team }
+      | Self::AcceptDraw { team } => team,
+    }
+  }
+}
+
+/// Head start given to the weaker team before a game begins: pieces it sets
+/// aside, which no longer count against it, and structures it gets on the
+/// board before the cathedral goes down.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Handicap {
+  team: Team,
+  removed: Vec<PieceKind>,
+  placed: Vec<(PieceKind, Rotation, Position)>,
+}
+
+impl Handicap {
+  /// Returns a handicap in favour of `team` that gives it nothing yet.
+  /// Panics if `team` is `Team::None`.
+  pub fn new(team: Team) -> Self {
+    assert_ne!(team, Team::None, "handicap must favour a playing team");
+    Self {
+      team,
+      removed: Vec::new(),
+      placed: Vec::new(),
+    }
+  }
+
+  /// Returns the usual graded handicap of given `level` for a game played by
+  /// `config`: the weaker team sets aside the `level` largest pieces of the
+  /// set it starts with.
+  pub fn graded(config: &GameConfig, team: Team, level: usize) -> Self {
+    let mut kinds =
+      PieceKind::for_team_in(team, config.piece_set, config.players).to_vec();
+    kinds.sort_by_key(|kind| Reverse(kind.square_count()));
+    kinds
+      .into_iter()
+      .take(level)
+      .fold(Self::new(team), Self::without)
+  }
+
+  /// Returns this handicap with a piece of `kind` taken out of the team's
+  /// hand.
+  pub fn without(mut self, kind: PieceKind) -> Self {
+    self.removed.push(kind);
+    self
+  }
+
+  /// Returns this handicap with a piece of `kind` from the team's hand
+  /// placed on the board, rotated to `rotation`, at `position`.
+  pub fn with_placed(
+    mut self,
+    kind: PieceKind,
+    rotation: Rotation,
+    position: Position,
+  ) -> Self {
+    self.placed.push((kind, rotation, position));
+    self
+  }
+
+  /// Returns team the handicap favours.
+  pub fn team(&self) -> Team {
+    self.team
+  }
+}
+
+/// Builds a game that starts from an arbitrary position: a board, pieces
+/// left in each team's hand and the team to move. Useful for puzzles and for
+/// resuming adjourned games.
+pub struct GameBuilder {
+  board: Board,
+  hands: HashMap<Team, PieceSet>,
+  turn: Option<Team>,
+  tie_break: TieBreak,
+}
+
+impl GameBuilder {
+  /// Returns a builder of a game on given `board`. Unless set otherwise,
+  /// each team holds its full set minus the pieces it has on the board, and
+  /// the team to move is the one a fresh game on the board would start with.
+  pub fn new(board: Board) -> Self {
+    Self {
+      board,
+      hands: HashMap::new(),
+      turn: None,
+      tie_break: TieBreak::default(),
+    }
+  }
+
+  /// Sets kinds of pieces `team` has in hand, duplicates included.
+  pub fn hand(mut self, team: Team, kinds: &[PieceKind]) -> Self {
+    let mut hand = PieceSet::empty(team);
+    for kind in kinds {
+      hand.return_piece(Piece::new(*kind, team));
+    }
+    self.hands.insert(team, hand);
+    self
+  }
+
+  /// Sets team that makes the next move.
+  pub fn turn(mut self, team: Team) -> Self {
+    self.turn = Some(team);
+    self
+  }
+
+  pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+    self.tie_break = tie_break;
+    self
+  }
+
+  /// Returns the game if the position is consistent: the board passes
+  /// `Board::validate`, pieces and hands only belong to playing teams and
+  /// the cathedral, a playing team is to move, white team while the cathedral is in the box, and no
+  /// team has more pieces of some kind on the board and in hand together
+  /// than its set holds.
+  pub fn build(self) -> Result<Game, SetupError> {
+    self.board.validate()?;
+    let mut game = Game::with_board(self.board);
+    let players = game.players();
+    if let Some(team) = self.hands.keys().find(|t| !players.contains(t)) {
+      return Err(SetupError::NotPlaying(*team));
+    }
+    if let Some(piece) = game
+      .board
+      .pieces()
+      .find(|p| p.team() != Team::None && !players.contains(&p.team()))
+    {
+      return Err(SetupError::NotPlaying(piece.team()));
+    }
+    let turn = self.turn.unwrap_or(game.turn);
+    if !players.contains(&turn) {
+      return Err(SetupError::InvalidTurn(turn));
+    }
+    if game.phase == Phase::Cathedral && turn != Team::White {
+      return Err(SetupError::CathedralNotPlaced);
+    }
+    for team in game.players() {
+      let on_board = game
+        .board
+        .pieces()
+        .filter(|piece| piece.team() == team)
+        .map(Piece::kind)
+        .collect::<Vec<_>>();
+      let hand = match self.hands.get(&team) {
+        Some(hand) => hand.clone(),
+        None => {
+          let mut hand = PieceSet::new(team);
+          for kind in &on_board {
+            hand.take(*kind);
+          }
+          hand
+        }
+      };
+      for kind in PieceKind::for_team(team) {
+        let count =
+          |kinds: &[PieceKind]| kinds.iter().filter(|k| *k == kind).count();
+        if count(&on_board) + count(hand.remaining())
+          > count(PieceKind::for_team(team))
+        {
+          return Err(SetupError::TooManyPieces(team, *kind));
+        }
+      }
+      game.hands.insert(team, hand);
+    }
+    game.turn = turn;
+    game.tie_break = self.tie_break;
+    Ok(game)
+  }
+}
+
+/// A move that was played, with what it resulted in.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlayedMove {
+  mv: Move,
+  outcome: PlacementOutcome,
+  undo: Option<PlacementUndo>,
+  /// State of the game before the move.
+  hands: HashMap<Team, PieceSet>,
+  turn: Team,
+  phase: Phase,
+  first_to_pass: Option<Team>,
+  draw_offer: Option<Team>,
+}
+
+impl PlayedMove {
+  pub fn mv(&self) -> Move {
+    self.mv
+  }
+
+  /// Returns pieces the move captured and tiles it claimed.
+  pub fn outcome(&self) -> &PlacementOutcome {
+    &self.outcome
+  }
+}
+
+pub trait GameState {}
+
+/// State of a game that may be in any phase, checked when moves are played.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum AnyPhase {}
+impl GameState for AnyPhase {}
+
+/// State of a game waiting for white team to place the cathedral.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CathedralPhase {}
+impl GameState for CathedralPhase {}
+
+/// State of a game where teams take turns placing their pieces.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PlayPhase {}
+impl GameState for PlayPhase {}
+
+/// State of a game that is over.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Finished {}
+impl GameState for Finished {}
+
+/// A game in progress: the board, pieces each team still has in hand and
+/// whose turn it is. White team places the cathedral, then black team makes
+/// the first move.
+///
+/// `Game` checks its phase whenever a move is played. A game in a given
+/// state, like `Game<PlayPhase>`, only offers moves that fit the phase and
+/// changes its type as the phase ends.
+pub struct Game<S: GameState = AnyPhase> {
+  board: Board,
+  hands: HashMap<Team, PieceSet>,
+  turn: Team,
+  phase: Phase,
+  tie_break: TieBreak,
+  piece_set: PieceSetPreset,
+  /// Team that passed before the other one did.
+  first_to_pass: Option<Team>,
+  /// Team that gave the game up.
+  resigned: Option<Team>,
+  /// Team whose draw offer waits for an answer.
+  draw_offer: Option<Team>,
+  /// Whether teams agreed to a draw.
+  draw_agreed: bool,
+  history: Vec<PlayedMove>,
+  /// Undone moves, the last undone one on top.
+  undone: Vec<Move>,
+  _state: PhantomData<S>,
+}
+
+/// What placing the cathedral led to.
+pub enum CathedralPlacement {
+  Placed(Game<PlayPhase>),
+  /// The placement was illegal, the game is unchanged.
+  Rejected(Game<CathedralPhase>, GameError),
+}
+
+/// What a move in the play phase led to.
+pub enum Progress {
+  Playing(Game<PlayPhase>),
+  Finished(Game<Finished>),
+  /// The move was illegal, the game is unchanged.
+  Rejected(Game<PlayPhase>, GameError),
+}
+
+/// A game with its phase known at compile time.
+pub enum PhasedGame {
+  Cathedral(Game<CathedralPhase>),
+  Playing(Game<PlayPhase>),
+  Finished(Game<Finished>),
+}
+
+impl Game {
+  /// Returns a new game on a standard board with full hands.
+  pub fn new() -> Self {
+    Self::with_board(Board::default())
+  }
+
+  /// Returns a new game that plays by given `config`. Panics if there are
+  /// fewer than two or more than four players.
+  pub fn with_config(config: GameConfig) -> Self {
+    assert!(
+      (2..=Team::PLAYERS.len()).contains(&config.players),
+      "game must have two to four players"
+    );
+    let board = Board::with_size_and_rules(config.board_size, config.rules);
+    let mut game = Self {
+      tie_break: config.tie_break,
+      piece_set: config.piece_set,
+      ..Self::with_board(board)
+    };
+    game
+      .hands
+      .extend(Team::PLAYERS[..config.players].iter().map(|team| {
+        let hand =
+          PieceSet::with_preset(*team, config.piece_set, config.players);
+        (*team, hand)
+      }));
+    game
+  }
+
+  /// Returns a new game that plays by given `config` and starts with
+  /// `handicap` applied. Pieces are set aside first, then placed in given
+  /// order. Fails if the team doesn't play by `config`, runs out of pieces
+  /// of some kind or a structure does not fit on the board.
+  pub fn with_handicap(
+    config: GameConfig,
+    handicap: &Handicap,
+  ) -> Result<Self, SetupError> {
+    let mut game = Self::with_config(config);
+    let hand = game
+      .hands
+      .get_mut(&handicap.team)
+      .ok_or(SetupError::NotPlaying(handicap.team))?;
+    for kind in &handicap.removed {
+      hand.take(*kind).ok_or(SetupError::PieceNotInHand(*kind))?;
+    }
+    for (kind, rotation, position) in &handicap.placed {
+      let mut piece =
+        hand.take(*kind).ok_or(SetupError::PieceNotInHand(*kind))?;
+      piece.rotate_to(*rotation);
+      game.board.try_place_piece(piece, *position)?;
+    }
+    Ok(game)
+  }
+
+  /// Returns a standard game with given `moves` played from the start.
+  /// Fails with index of the first move that can't be played.
+  pub fn from_moves(moves: &[Move]) -> Result<Self, ReplayError> {
+    let mut game = Self::new();
+    for (index, mv) in moves.iter().enumerate() {
+      game
+        .play(*mv)
+        .map_err(|error| ReplayError::IllegalMove { index, error })?;
+    }
+    Ok(game)
+  }
+
+  /// Same as `from_moves` but with moves in text notation, separated by
+  /// whitespace, as `to_notation` writes them.
+  pub fn from_notation(notation: &str) -> Result<Self, ReplayError> {
+    let moves = notation
+      .split_whitespace()
+      .enumerate()
+      .map(|(index, mv)| {
+        mv.parse()
+          .map_err(|error| ReplayError::InvalidMove { index, error })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    Self::from_moves(&moves)
+  }
+
+  /// Returns a new game of two teams played on given `board` with full
+  /// hands. The game starts with the cathedral phase unless the cathedral
+  /// has already left the box.
+  pub fn with_board(board: Board) -> Self {
+    let (phase, turn, cathedral) = match board.cathedral_status() {
+      CathedralStatus::InBox => {
+        (Phase::Cathedral, Team::White, PieceSet::new(Team::None))
+      }
+      _ => (Phase::Pieces, Team::Black, PieceSet::empty(Team::None)),
+    };
+    let hands = [Team::White, Team::Black]
+      .into_iter()
+      .map(|team| (team, PieceSet::new(team)))
+      .chain([(Team::None, cathedral)])
+      .collect();
+    Self {
+      board,
+      hands,
+      turn,
+      phase,
+      tie_break: TieBreak::default(),
+      piece_set: PieceSetPreset::default(),
+      first_to_pass: None,
+      resigned: None,
+      draw_offer: None,
+      draw_agreed: false,
+      history: Vec::new(),
+      undone: Vec::new(),
+      _state: PhantomData,
+    }
+  }
+
+  /// Starts the game over on the same board with given `rules`, reusing
+  /// allocated memory.
+  pub fn reset_with(&mut self, rules: Rules) {
+    self.board.reset_with(rules);
+    for hand in self.hands.values_mut() {
+      hand.refill();
+    }
+    self.phase = Phase::Cathedral;
+    self.turn = Team::White;
+    self.first_to_pass = None;
+    self.resigned = None;
+    self.draw_offer = None;
+    self.draw_agreed = false;
+    self.history.clear();
+    self.undone.clear();
+  }
+
+  /// Tries to make a move. The move must be made by the team whose turn it
+  /// is, with a piece from its hand, and be a legal placement on the board.
+  /// Until the cathedral is down, the only such move is white team placing
+  /// it. Captured pieces go back to their owners' hands, then the turn passes
+  /// to the next team. Returns what the placement resulted in. No moves can
+  /// be made once a team has resigned or teams agreed to a draw, and passing
+  /// fails once no team can place anything. Playing a move forgets moves
+  /// that could be redone.
+  pub fn play(&mut self, mv: Move) -> Result<PlacementOutcome, GameError> {
+    let outcome = self.record(mv)?;
+    self.undone.clear();
+    Ok(outcome)
+  }
+
+  /// Plays `mv` regardless of whose turn it is, for variants where teams
+  /// move at once.
+  #[cfg(feature = "unstable")]
+  pub(crate) fn play_out_of_turn(
+    &mut self,
+    mv: Move,
+  ) -> Result<PlacementOutcome, GameError> {
+    let turn = std::mem::replace(&mut self.turn, mv.team());
+    let result = self.play(mv);
+    if result.is_err() {
+      self.turn = turn;
+    }
+    result
+  }
+
+  /// Checks whether `mv` could be played now regardless of whose turn it is,
+  /// without changing the game.
+  #[cfg(feature = "unstable")]
+  pub(crate) fn check_out_of_turn(
+    &mut self,
+    mv: Move,
+  ) -> Result<(), GameError> {
+    let turn = std::mem::replace(&mut self.turn, mv.team());
+    let result = self.dry_run(mv, |_, _| ());
+    self.turn = turn;
+    result
+  }
+
+  /// Plays `mv`, lets `inspect` look at the game and what the move resulted
+  /// in, then takes the move back. The game is left as it was, moves that
+  /// could be redone included.
+  pub(crate) fn dry_run<T>(
+    &mut self,
+    mv: Move,
+    inspect: impl FnOnce(&Self, &PlacementOutcome) -> T,
+  ) -> Result<T, GameError> {
+    let outcome = self.record(mv)?;
+    let result = inspect(self, &outcome);
+    self.undo();
+    self.undone.pop();
+    Ok(result)
+  }
+
+  /// Takes the last played move back, restoring the game exactly as it was
+  /// before the move. Returns the undone move or `None` if no moves were
+  /// played.
+  pub fn undo(&mut self) -> Option<Move> {
+    let played = self.history.pop()?;
+    if let Some(undo) = played.undo {
+      self.board.undo_placement(undo);
+    }
+    self.hands = played.hands;
+    self.turn = played.turn;
+    self.phase = played.phase;
+    self.first_to_pass = played.first_to_pass;
+    self.draw_offer = played.draw_offer;
+    // No moves can follow a resignation or an agreed draw, so the game
+    // wasn't over before.
+    self.resigned = None;
+    self.draw_agreed = false;
+    self.undone.push(played.mv);
+    Some(played.mv)
+  }
+
+  /// Plays the last undone move again. Returns the move or `None` if there
+  /// is nothing to redo.
+  pub fn redo(&mut self) -> Option<Move> {
+    let mv = self.undone.pop()?;
+    self.record(mv).expect("undone move must be legal again");
+    Some(mv)
+  }
+
+  /// Returns `true` if no other team could capture the piece `mv` puts down
+  /// with a single placement right after it. Moves that place no piece are
+  /// always safe. The move is tried out and taken back, so the game is left
+  /// as it was. Fails if `mv` is not legal now.
+  pub fn is_safe_placement(&mut self, mv: Move) -> Result<bool, GameError> {
+    self.dry_run(mv, |game, outcome| {
+      let Some(id) = outcome.placed_id else {
+        return true;
+      };
+      !game.players().into_iter().any(|team| {
+        team != mv.team()
+          && game.placements_for(team).into_iter().any(|reply| {
+            let Move::Place {
+              kind,
+              rotation,
+              position,
+              ..
+            } = reply
+            else {
+              return false;
+            };
+            let mut piece = Piece::new(kind, team);
+            piece.rotate_to(rotation);
+            game
+              .board
+              .preview_placement(&piece, position)
+              .is_ok_and(|outcome| outcome.captured_ids.contains(&id))
+          })
+      })
+    })
+  }
+
+  /// Makes a move and adds it to the history.
+  fn record(&mut self, mv: Move) -> Result<PlacementOutcome, GameError> {
+    let hands = self.hands.clone();
+    let (turn, phase, first_to_pass, draw_offer) =
+      (self.turn, self.phase, self.first_to_pass, self.draw_offer);
+    let (outcome, undo) = self.apply(mv)?;
+    if matches!(mv, Move::Place { .. } | Move::Pass { .. })
+      && draw_offer.is_some_and(|team| team != mv.team())
+    {
+      self.draw_offer = None;
+    }
+    self.history.push(PlayedMove {
+      mv,
+      outcome: outcome.clone(),
+      undo,
+      hands,
+      turn,
+      phase,
+      first_to_pass,
+      draw_offer,
+    });
+    Ok(outcome)
+  }
+
+  /// Makes a move, returning its outcome and what it changed on the board.
+  fn apply(
+    &mut self,
+    mv: Move,
+  ) -> Result<(PlacementOutcome, Option<PlacementUndo>), GameError> {
+    if self.resigned.is_some() || self.draw_agreed {
+      return Err(GameError::GameOver);
+    }
+    self.check_playing(mv.team())?;
+    let (team, kind, rotation, position) = match mv {
+      Move::Place {
+        team,
+        kind,
+        rotation,
+        position,
+      } => (team, kind, rotation, position),
+      Move::Pass { team } => {
+        if team != self.turn {
+          return Err(GameError::NotYourTurn(team));
+        }
+        if !self.must_pass(team) {
+          return Err(GameError::CannotPass);
+        }
+        if self.players().into_iter().all(|team| self.must_pass(team)) {
+          return Err(GameError::GameOver);
+        }
+        self.first_to_pass.get_or_insert(team);
+        self.turn = self.next_player(team);
+        return Ok((PlacementOutcome::default(), None));
+      }
+      Move::Resign { team } => {
+        self.resigned = Some(team);
+        return Ok((PlacementOutcome::default(), None));
+      }
+      Move::OfferDraw { team } => {
+        self.draw_offer = Some(team);
+        return Ok((PlacementOutcome::default(), None));
+      }
+      Move::AcceptDraw { team } => {
+        if !matches!(self.draw_offer, Some(t) if t != team) {
+          return Err(GameError::NoDrawOffer);
+        }
+        self.draw_agreed = true;
+        return Ok((PlacementOutcome::default(), None));
+      }
+    };
+    if team != self.turn {
+      return Err(GameError::NotYourTurn(team));
+    }
+    let owner = match self.phase {
+      Phase::Cathedral if kind != PieceKind::Cathedral => {
+        return Err(GameError::CathedralNotPlaced);
+      }
+      Phase::Cathedral => Team::None,
+      Phase::Pieces if kind == PieceKind::Cathedral => {
+        return Err(GameError::CathedralAlreadyPlaced);
+      }
+      Phase::Pieces => team,
+    };
+
+    let hand = self.hands.get_mut(&owner).expect("team must have a hand");
+    let mut piece = hand.take(kind).ok_or(GameError::PieceNotInHand(kind))?;
+    piece.rotate_to(rotation);
+    let placement =
+      self.board.try_place_piece_undoable(piece.clone(), position);
+    let (outcome, undo) = match placement {
+      Ok(placement) => placement,
+      Err(e) => {
+        hand.return_piece(piece);
+        return Err(e.into());
+      }
+    };
+    for piece in &outcome.captured_pieces {
+      if piece.kind() != PieceKind::Cathedral {
+        self
+          .hands
+          .get_mut(&piece.team())
+          .expect("team must have a hand")
+          .return_piece(piece.clone());
+      }
+    }
+
+    if self.phase == Phase::Cathedral {
+      self.phase = Phase::Pieces;
+      self.turn = self.next_player(Team::White);
+      return Ok((outcome, Some(undo)));
+    }
+
+    self.turn = self.next_player(team);
+    Ok((outcome, Some(undo)))
+  }
+
+  /// Returns result of the game once it is over: the team with fewer squares
+  /// of pieces left in hand wins, equal counts are resolved by the game's
+  /// tie-break rule. A team that resigned loses and an agreed draw is a draw
+  /// regardless of the score.
+  pub fn result(&self) -> Option<GameResult> {
+    self.is_over().then(|| self.decide())
+  }
+
+  /// Returns game's phase as a type, so moves that don't fit the phase
+  /// can't be attempted.
+  pub fn into_phased(self) -> PhasedGame {
+    if self.is_over() {
+      PhasedGame::Finished(self.cast())
+    } else if self.phase == Phase::Cathedral {
+      PhasedGame::Cathedral(self.cast())
+    } else {
+      PhasedGame::Playing(self.cast())
+    }
+  }
+}
+
+impl Game<CathedralPhase> {
+  /// Returns a new game that plays by given `config`, waiting for the
+  /// cathedral.
+  pub fn start(config: GameConfig) -> Self {
+    Game::with_config(config).cast()
+  }
+
+  /// Places the cathedral for white team, starting the play phase.
+  pub fn place_cathedral(
+    self,
+    rotation: Rotation,
+    position: Position,
+  ) -> CathedralPlacement {
+    let mut game = self.cast::<AnyPhase>();
+    let mv = Move::Place {
+      team: Team::White,
+      kind: PieceKind::Cathedral,
+      rotation,
+      position,
+    };
+    match game.play(mv) {
+      Ok(_) => CathedralPlacement::Placed(game.cast()),
+      Err(e) => CathedralPlacement::Rejected(game.cast(), e),
+    }
+  }
+}
+
+impl Game<PlayPhase> {
+  /// Plays a move of the play phase, see `Game::play`.
+  pub fn play(self, mv: Move) -> Progress {
+    let mut game = self.cast::<AnyPhase>();
+    match game.play(mv) {
+      Ok(_) if game.is_over() => Progress::Finished(game.cast()),
+      Ok(_) => Progress::Playing(game.cast()),
+      Err(e) => Progress::Rejected(game.cast(), e),
+    }
+  }
+}
+
+impl Game<Finished> {
+  /// Returns how the game ended, see `Game::result`.
+  pub fn result(&self) -> GameResult {
+    self.decide()
+  }
+}
+
+impl<S: GameState> Game<S> {
+  /// Returns this game as one in state `T`, keeping everything else.
+  fn cast<T: GameState>(self) -> Game<T> {
+    Game {
+      board: self.board,
+      hands: self.hands,
+      turn: self.turn,
+      phase: self.phase,
+      tie_break: self.tie_break,
+      piece_set: self.piece_set,
+      first_to_pass: self.first_to_pass,
+      resigned: self.resigned,
+      draw_offer: self.draw_offer,
+      draw_agreed: self.draw_agreed,
+      history: self.history,
+      undone: self.undone,
+      _state: PhantomData,
+    }
+  }
+
+  /// Returns this game with its phase checked on every move again.
+  pub fn into_any_phase(self) -> Game {
+    self.cast()
+  }
+
+  /// Returns result the game would have if it ended now. With more than
+  /// two teams a resignation ends the game for everyone, and the rest of the
+  /// teams are ranked by their scores.
+  fn decide(&self) -> GameResult {
+    if self.draw_agreed {
+      return GameResult::Agreed;
+    }
+    let mut players = self.players();
+    match self.resigned {
+      Some(team) if players.len() == 2 => {
+        return GameResult::Resignation(team);
+      }
+      Some(team) => players.retain(|t| *t != team),
+      None => {}
+    }
+    let sole_winner = |teams: &[Team]| match teams {
+      [team] => GameResult::Winner(*team),
+      _ => GameResult::Draw,
+    };
+    let scoring = self.board.rules().scoring;
+    let score = self.score();
+    let penalty = |team: &Team| score[team].penalty(scoring);
+    let lowest = players.iter().map(penalty).min();
+    players.retain(|team| Some(penalty(team)) == lowest);
+    if players.len() == 1 {
+      return sole_winner(&players);
+    }
+    match self.tie_break {
+      TieBreak::Shared => GameResult::Draw,
+      TieBreak::Territory => {
+        let territory = |team: &Team| score[team].territory;
+        let most = players.iter().map(territory).max();
+        players.retain(|team| Some(territory(team)) == most);
+        sole_winner(&players)
+      }
+      TieBreak::FirstToFinish => self
+        .first_to_pass
+        .filter(|team| players.contains(team))
+        .map_or(GameResult::Draw, GameResult::Winner),
+    }
+  }
+
+  /// Returns teams taking part in the game, in order they take turns.
+  pub fn players(&self) -> Vec<Team> {
+    Team::PLAYERS
+      .into_iter()
+      .filter(|team| self.hands.contains_key(team))
+      .collect()
+  }
+
+  /// Returns an error if `team` doesn't take part in the game.
+  fn check_playing(&self, team: Team) -> Result<(), GameError> {
+    if !self.players().contains(&team) {
+      return Err(GameError::NotPlaying(team));
+    }
+    Ok(())
+  }
+
+  /// Returns team that moves after `team`.
+  fn next_player(&self, team: Team) -> Team {
+    let players = self.players();
+    let index = players
+      .iter()
+      .position(|t| *t == team)
+      .expect("team must take part in the game");
+    players[(index + 1) % players.len()]
+  }
+
+  pub fn board(&self) -> &Board {
+    &self.board
+  }
+
+  /// Returns options this game plays by.
+  pub fn config(&self) -> GameConfig {
+    GameConfig {
+      rules: self.board.rules(),
+      tie_break: self.tie_break,
+      board_size: self.board.size().x,
+      players: self.players().len(),
+      piece_set: self.piece_set,
+    }
+  }
+
+  pub fn phase(&self) -> Phase {
+    self.phase
+  }
+
+  /// Returns whether the cathedral is still in the box, on the board or
+  /// captured.
+  pub fn cathedral_status(&self) -> CathedralStatus {
+    self.board.cathedral_status()
+  }
+
+  /// Returns team that makes the next move.
+  pub fn turn(&self) -> Team {
+    self.turn
+  }
+
+  /// Returns team whose draw offer waits for the other team's answer.
+  pub fn draw_offer(&self) -> Option<Team> {
+    self.draw_offer
+  }
+
+  /// Returns pieces `team` still has in hand, or `None` if the team isn't
+  /// playing. `Team::None` holds the cathedral until it is placed.
+  pub fn get_hand(&self, team: Team) -> Option<&PieceSet> {
+    self.hands.get(&team)
+  }
+
+  /// Returns pieces `team` still has in hand. `Team::None` holds the
+  /// cathedral until it is placed. Panics if the team isn't playing.
+  pub fn hand(&self, team: Team) -> &PieceSet {
+    self
+      .get_hand(team)
+      .unwrap_or_else(|| panic!("{team:?} team isn't playing"))
+  }
+
+  /// Returns moves played so far, in order.
+  pub fn history(&self) -> &[PlayedMove] {
+    &self.history
+  }
+
+  /// Returns moves played so far in text notation, separated by spaces.
+  pub fn to_notation(&self) -> String {
+    self
+      .history
+      .iter()
+      .map(|played| played.mv.to_string())
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
+  /// Returns every move the team whose turn it is can legally make. A team
+  /// with no placements left can only pass. Resigning and draw offers are
+  /// always possible and are not listed.
+  pub fn legal_moves(&self) -> Vec<Move> {
+    let moves = self.placements_for(self.turn);
+    if moves.is_empty() {
+      return vec![Move::Pass { team: self.turn }];
+    }
+    moves
+  }
+
+  /// Same as `legal_moves` but keeps a single move out of those that are
+  /// mirror images or rotations of each other under a symmetry of the current
+  /// board. Near-empty boards in the opening plies are symmetric, so search
+  /// engines get a much smaller branching factor there; on asymmetric boards
+  /// this returns the same moves as `legal_moves`.
+  pub fn legal_moves_up_to_symmetry(&self) -> Vec<Move> {
+    let symmetries = self.board.symmetries();
+    let size = self.board.size().x;
+    let mut seen = HashSet::new();
+    self
+      .legal_moves()
+      .into_iter()
+      .filter(|mv| {
+        let Some((kind, footprint)) = placement_footprint(*mv) else {
+          return true;
+        };
+        let canonical = symmetries
+          .iter()
+          .map(|symmetry| {
+            let mut mapped = footprint
+              .iter()
+              .map(|p| symmetry.apply(*p, size))
+              .collect::<Vec<_>>();
+            mapped.sort();
+            mapped
+          })
+          .min();
+        seen.insert((kind, canonical))
+      })
+      .collect()
+  }
+
+  /// Returns `true` if none of pieces `team` has in hand fit anywhere on the
+  /// board, so the team has to pass its turns. Teams that aren't playing
+  /// have nothing to place.
+  pub fn must_pass(&self, team: Team) -> bool {
+    self.placements_for(team).is_empty()
+  }
+
+  /// Returns `true` if a team has resigned, teams agreed to a draw or no
+  /// team can place any more pieces.
+  pub fn is_over(&self) -> bool {
+    self.resigned.is_some()
+      || self.draw_agreed
+      || self.phase == Phase::Pieces
+        && self.players().into_iter().all(|team| self.must_pass(team))
+  }
+
+  /// Returns score of both teams. Unplaced squares are counted from what
+  /// the teams have in hand.
+  pub fn score(&self) -> HashMap<Team, Score> {
+    self
+      .players()
+      .into_iter()
+      .map(|team| {
+        let score = Score {
+          unplaced_squares: self.hand(team).remaining_squares(),
+          ..self
+            .board
+            .score_in(team, self.piece_set, self.players().len())
+        };
+        (team, score)
+      })
+      .collect()
+  }
+
+  /// Returns what `team`'s score is made of: pieces left in hand, pieces it
+  /// captured and data the tie-break rule looks at. Returns `None` if the
+  /// team isn't playing.
+  pub fn score_breakdown(&self, team: Team) -> Option<ScoreBreakdown> {
+    if !self.players().contains(&team) {
+      return None;
+    }
+    let hand = self.hand(team);
+    Some(ScoreBreakdown {
+      team,
+      unplaced: hand
+        .remaining()
+        .iter()
+        .map(|kind| (*kind, kind.square_count()))
+        .collect(),
+      unplaced_squares: hand.remaining_squares(),
+      captured: self.board.captured_by(team).to_vec(),
+      territory: self.board.score_for(team).territory,
+      scoring: self.board.rules().scoring,
+      penalty: self.score()[&team].penalty(self.board.rules().scoring),
+      tie_break: self.tie_break,
+      passed_first: self.first_to_pass == Some(team),
+    })
+  }
+
+  /// Returns every placement `team` could make if it was its turn.
+  fn placements_for(&self, team: Team) -> Vec<Move> {
+    let owner = match self.phase {
+      Phase::Cathedral if team == Team::White => Team::None,
+      _ => team,
+    };
+    let Some(hand) = self.get_hand(owner) else {
+      return Vec::new();
+    };
+    let mut kinds = hand.remaining().to_vec();
+    kinds.sort();
+    kinds.dedup();
+
+    let mut moves = Vec::new();
+    for kind in kinds {
+      let piece = Piece::new(kind, owner);
+      for (rotation, position) in self.board.legal_placements(&piece) {
+        moves.push(Move::Place {
+          team,
+          kind,
+          rotation,
+          position,
+        });
+      }
+    }
+    moves
+  }
+
+  /// Returns `false` if `mv` can't be legal as the next move of its team,
+  /// whatever the other team does first. It is a cheap over-approximation:
+  /// the move must be legal on the current board, ignoring the turn, and use
+  /// a piece the team has in hand or, while waiting for the other team, one
+  /// that a capture may return to it. Moves that pass can still turn out
+  /// illegal by the time they are played.
+  pub fn is_plausible_premove(&self, mv: Move) -> bool {
+    let (team, kind, rotation, position) = match mv {
+      Move::Place {
+        team,
+        kind,
+        rotation,
+        position,
+      } => (team, kind, rotation, position),
+      Move::Pass { team }
+      | Move::Resign { team }
+      | Move::OfferDraw { team }
+      | Move::AcceptDraw { team } => {
+        return self.check_playing(team).is_ok();
+      }
+    };
+    if self.check_playing(team).is_err() {
+      return false;
+    }
+    let mut piece = if kind == PieceKind::Cathedral {
+      if self.phase != Phase::Cathedral || team != Team::White {
+        return false;
+      }
+      Piece::new_cathedral()
+    } else {
+      if self.phase == Phase::Cathedral && team == Team::White {
+        return false;
+      }
+      let waiting = team != self.turn || self.phase == Phase::Cathedral;
+      let available = self.hand(team).contains(kind)
+        || waiting
+          && self
+            .board
+            .pieces()
+            .any(|p| p.team() == team && p.kind() == kind);
+      if !available {
+        return false;
+      }
+      Piece::new(kind, team)
+    };
+    piece.rotate_to(rotation);
+    self.board.can_place_piece(&piece, position).is_ok()
+  }
+}
+
+impl Default for Game {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Writes move in compact notation: team letter, a colon and either `PASS`,
+/// `RESIGN`, `OFFER_DRAW`, `ACCEPT_DRAW` or the placement as
+/// `PIECE@square^rotation`, e.g.
+/// `W:ACADEMY@c4^R`. Squares are named like in chess, with column letters
+/// and row numbers starting from `a1` at position (0, 0). Rotations are
+/// `U`, `R`, `D` and `L`.
+impl Display for Move {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let team_letter = |team| match team {
+      Team::White => 'W',
+      Team::Black => 'B',
+      Team::Red => 'R',
+      Team::Green => 'G',
+      Team::None => 'N',
+    };
+    match *self {
+      Self::Place {
+        team,
+        kind,
+        rotation,
+        position,
+      } => {
+        let name = kind.name().to_ascii_uppercase();
+        let rotation = match rotation {
+          Rotation::UP => 'U',
+          Rotation::RIGHT => 'R',
+          Rotation::DOWN => 'D',
+          Rotation::LEFT => 'L',
+        };
+        write!(f, "{}:{name}@", team_letter(team))?;
+        write_column(f, position.y)?;
+        write!(f, "{}^{rotation}", position.x + 1)
+      }
+      Self::Pass { team } => write!(f, "{}:PASS", team_letter(team)),
+      Self::Resign { team } => write!(f, "{}:RESIGN", team_letter(team)),
+      Self::OfferDraw { team } => {
+        write!(f, "{}:OFFER_DRAW", team_letter(team))
+      }
+      Self::AcceptDraw { team } => {
+        write!(f, "{}:ACCEPT_DRAW", team_letter(team))
+      }
+    }
+  }
+}
+
+impl FromStr for Move {
+  type Err = ParseMoveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (team, rest) =
+      s.split_once(':').ok_or(ParseMoveError::InvalidFormat)?;
+    let team = match team {
+      "W" => Team::White,
+      "B" => Team::Black,
+      "R" => Team::Red,
+      "G" => Team::Green,
+      "N" => Team::None,
+      _ => return Err(ParseMoveError::InvalidTeam),
+    };
+    match rest {
+      "PASS" => return Ok(Self::Pass { team }),
+      "RESIGN" => return Ok(Self::Resign { team }),
+      "OFFER_DRAW" => return Ok(Self::OfferDraw { team }),
+      "ACCEPT_DRAW" => return Ok(Self::AcceptDraw { team }),
+      _ => {}
+    }
+
+    let (name, rest) =
+      rest.split_once('@').ok_or(ParseMoveError::InvalidFormat)?;
+    let (square, rotation) =
+      rest.split_once('^').ok_or(ParseMoveError::InvalidFormat)?;
+    let kind = PieceKind::ALL
+      .into_iter()
+      .find(|kind| kind.name().to_ascii_uppercase() == name)
+      .ok_or_else(|| ParseMoveError::InvalidPiece(name.to_owned()))?;
+    let position = parse_square(square)
+      .ok_or_else(|| ParseMoveError::InvalidSquare(square.to_owned()))?;
+    let rotation = match rotation {
+      "U" => Rotation::UP,
+      "R" => Rotation::RIGHT,
+      "D" => Rotation::DOWN,
+      "L" => Rotation::LEFT,
+      _ => return Err(ParseMoveError::InvalidRotation),
+    };
+    Ok(Self::Place {
+      team,
+      kind,
+      rotation,
+      position,
+    })
+  }
+}
+
+/// Writes column `y` as letters: `a` to `z`, then `aa`, `ab` and so on.
+fn write_column(f: &mut std::fmt::Formatter, y: usize) -> std::fmt::Result {
+  if y >= 26 {
+    write_column(f, y / 26 - 1)?;
+  }
+  write!(f, "{}", char::from(b'a' + (y % 26) as u8))
+}
+
+/// Parses square like `c4` into position, inverse of how moves are written.
+fn parse_square(square: &str) -> Option<Position> {
+  let digits = square.find(|c: char| !c.is_ascii_lowercase())?;
+  let (letters, row) = square.split_at(digits);
+  if letters.is_empty() || !row.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  let y = letters.bytes().try_fold(0usize, |y, b| {
+    y.checked_mul(26)?.checked_add(usize::from(b - b'a') + 1)
+  })?
+    - 1;
+  let x = row.parse::<usize>().ok()?.checked_sub(1)?;
+  Some(Position { x, y })
+}
+
+/// Returns kind of the piece a placement move puts down along with tiles
+/// it covers. Other moves have no footprint.
+fn placement_footprint(mv: Move) -> Option<(PieceKind, Vec<Position>)> {
+  match mv {
+    Move::Place {
+      team,
+      kind,
+      rotation,
+      position,
+    } => {
+      let mut piece = Piece::new(kind, team);
+      piece.rotate_to(rotation);
+      Some((kind, piece.occupied_positions_iter(position).collect()))
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    board::{Symmetry, Tile},
+    consts::{CATHEDRAL_SQUARES, PIECES_PER_TEAM, SQUARES_PER_TEAM},
+    error::{BoardError, InvalidBoardError},
+    rules::Connectivity,
+    testing::BoardBuilder,
+  };
+
+  fn place(team: Team, kind: PieceKind, position: (usize, usize)) -> Move {
+    Move::Place {
+      team,
+      kind,
+      rotation: Rotation::UP,
+      position: position.into(),
+    }
+  }
+
+  /// Returns a game with the cathedral placed at (3, 3).
+  fn started_game() -> Game {
+    let mut game = Game::new();
+    game
+      .play(place(Team::White, PieceKind::Cathedral, (3, 3)))
+      .expect("cathedral must fit");
+    game
+  }
+
+  #[test]
+  fn test_cathedral_phase() -> Result<(), GameError> {
+    let mut game = Game::new();
+    assert_eq!(game.phase(), Phase::Cathedral);
+    assert_eq!(game.cathedral_status(), CathedralStatus::InBox);
+    assert_eq!(game.turn(), Team::White);
+    assert_eq!(
+      game.play(place(Team::Black, PieceKind::Cathedral, (3, 3))),
+      Err(GameError::NotYourTurn(Team::Black))
+    );
+    assert_eq!(
+      game.play(place(Team::White, PieceKind::Tavern, (0, 0))),
+      Err(GameError::CathedralNotPlaced)
+    );
+
+    game.play(place(Team::White, PieceKind::Cathedral, (3, 3)))?;
+    assert_eq!(game.phase(), Phase::Pieces);
+    assert_eq!(
+      game.cathedral_status(),
+      CathedralStatus::Placed((3, 3).into())
+    );
+    assert_eq!(game.turn(), Team::Black);
+    assert_eq!(game.hand(Team::White).remaining().len(), PIECES_PER_TEAM);
+
+    let mut board = Board::default();
+    board.place_piece(Piece::new_cathedral(), (3, 3).into());
+    assert_eq!(Game::with_board(board).phase(), Phase::Pieces);
+    Ok(())
+  }
+
+  #[test]
+  fn test_turn_order() -> Result<(), GameError> {
+    let mut game = started_game();
+    assert_eq!(game.turn(), Team::Black);
+    assert_eq!(
+      game.play(place(Team::White, PieceKind::Tavern, (0, 0))),
+      Err(GameError::NotYourTurn(Team::White))
+    );
+
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    assert_eq!(game.turn(), Team::White);
+    assert_eq!(
+      game.hand(Team::Black).remaining().len(),
+      PIECES_PER_TEAM - 1
+    );
+    assert_eq!(game.hand(Team::White).remaining().len(), PIECES_PER_TEAM);
+
+    game.play(place(Team::White, PieceKind::Tavern, (9, 9)))?;
+    assert_eq!(game.turn(), Team::Black);
+    Ok(())
+  }
+
+  #[test]
+  fn test_hand() -> Result<(), GameError> {
+    let mut game = started_game();
+    game.play(place(Team::Black, PieceKind::Castle, (0, 0)))?;
+    game.play(place(Team::White, PieceKind::Castle, (7, 7)))?;
+    assert_eq!(
+      game.play(place(Team::Black, PieceKind::Castle, (5, 5))),
+      Err(GameError::PieceNotInHand(PieceKind::Castle))
+    );
+    assert_eq!(
+      game.play(place(Team::Black, PieceKind::Cathedral, (5, 5))),
+      Err(GameError::CathedralAlreadyPlaced)
+    );
+    assert!(game.hand(Team::None).is_empty());
+    assert!(game.get_hand(Team::Red).is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_illegal_placement_keeps_turn() {
+    let mut game = started_game();
+    assert_eq!(
+      game.play(place(Team::Black, PieceKind::Tavern, (10, 0))),
+      Err(GameError::Board(BoardError::PieceOutOfBounds(
+        (10, 0).into()
+      )))
+    );
+    assert_eq!(game.turn(), Team::Black);
+    assert_eq!(game.hand(Team::Black).remaining().len(), PIECES_PER_TEAM);
+  }
+
+  #[test]
+  fn test_captured_piece_returns_to_hand() -> Result<(), GameError> {
+    let mut game = started_game();
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    game.play(place(Team::White, PieceKind::Tavern, (0, 1)))?;
+    game.play(place(Team::Black, PieceKind::Tavern, (9, 9)))?;
+    let outcome = game.play(place(Team::White, PieceKind::Tavern, (1, 0)))?;
+    assert_eq!(outcome.captured_pieces, [Piece::new_tavern(Team::Black)]);
+    assert_eq!(
+      game.hand(Team::Black).remaining().len(),
+      PIECES_PER_TEAM - 1
+    );
+    assert_eq!(
+      game.hand(Team::White).remaining().len(),
+      PIECES_PER_TEAM - 2
+    );
+
+    let score = game.score();
+    assert_eq!(score[&Team::White].unplaced_squares, SQUARES_PER_TEAM - 2);
+    assert_eq!(score[&Team::White].territory, 1);
+    assert_eq!(score[&Team::White].captured_pieces, 1);
+    assert_eq!(score[&Team::Black].unplaced_squares, SQUARES_PER_TEAM - 1);
+    Ok(())
+  }
+
+  #[test]
+  fn test_is_plausible_premove() -> Result<(), GameError> {
+    let plausible = |game: &Game, team, kind, position| {
+      game.is_plausible_premove(place(team, kind, position))
+    };
+    let mut game = Game::new();
+    assert!(plausible(&game, Team::Black, PieceKind::Tavern, (0, 0)));
+    assert!(!plausible(&game, Team::White, PieceKind::Tavern, (0, 0)));
+    assert!(!plausible(&game, Team::Red, PieceKind::Tavern, (0, 0)));
+    assert!(!plausible(&game, Team::None, PieceKind::Cathedral, (3, 3)));
+
+    game = started_game();
+    game.play(place(Team::Black, PieceKind::Castle, (0, 0)))?;
+    assert!(plausible(&game, Team::White, PieceKind::Tavern, (9, 9)));
+    assert!(!plausible(&game, Team::White, PieceKind::Tavern, (0, 0)));
+    // Black castle is on the board, but may return to hand after a capture.
+    assert!(plausible(&game, Team::Black, PieceKind::Castle, (7, 7)));
+    assert!(!plausible(&game, Team::Black, PieceKind::Tavern, (0, 0)));
+    assert!(!plausible(&game, Team::Black, PieceKind::Cathedral, (7, 7)));
+
+    // On black team's own turn, only pieces in hand count.
+    game.play(place(Team::White, PieceKind::Castle, (7, 7)))?;
+    assert!(!plausible(&game, Team::Black, PieceKind::Castle, (0, 5)));
+    Ok(())
+  }
+
+  #[test]
+  fn test_legal_moves_up_to_symmetry() -> Result<(), GameError> {
+    let mut game = Game::new();
+    let all = game.legal_moves();
+    let pruned = game.legal_moves_up_to_symmetry();
+    assert!(pruned.len() * 6 < all.len());
+    assert!(pruned.iter().all(|mv| all.contains(mv)));
+    let size = game.board().size().x;
+    let image = |mv: Move, symmetry: Symmetry| {
+      let (kind, footprint) = placement_footprint(mv).expect("must place");
+      let mut mapped = footprint
+        .iter()
+        .map(|p| symmetry.apply(*p, size))
+        .collect::<Vec<_>>();
+      mapped.sort();
+      (kind, mapped)
+    };
+    for mv in &all {
+      assert!(pruned.iter().any(|kept| {
+        Symmetry::ALL.into_iter().any(|symmetry| {
+          image(*kept, symmetry) == image(*mv, Symmetry::Identity)
+        })
+      }));
+    }
+
+    game.play(Move::Place {
+      team: Team::White,
+      kind: PieceKind::Cathedral,
+      rotation: Rotation::UP,
+      position: Position { x: 3, y: 3 },
+    })?;
+    assert_eq!(game.board().symmetries(), [Symmetry::Identity]);
+    assert_eq!(game.legal_moves_up_to_symmetry(), game.legal_moves());
+    Ok(())
+  }
+
+  #[test]
+  fn test_legal_moves() -> Result<(), GameError> {
+    let mut game = Game::new();
+    let moves = game.legal_moves();
+    assert!(!moves.is_empty());
+    assert!(moves.iter().all(|mv| matches!(mv, Move::Place {
+      team: Team::White,
+      kind: PieceKind::Cathedral,
+      ..
+    })));
+
+    game.play(moves[0])?;
+    let moves = game.legal_moves();
+    assert!(moves.iter().all(|mv| matches!(mv, Move::Place {
+      team: Team::Black,
+      ..
+    })));
+    let taverns = moves
+      .iter()
+      .filter(|mv| {
+        matches!(mv, Move::Place {
+          kind: PieceKind::Tavern,
+          ..
+        })
+      })
+      .count();
+    assert_eq!(taverns, 100 - 6);
+    for mv in moves {
+      assert!(game.is_plausible_premove(mv));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_pass() -> Result<(), GameError> {
+    let board = BoardBuilder::new(
+      "
+      b C b b
+      C C C b
+      b C b b
+      b C b b
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let mut game = Game::with_board(board);
+    assert!(!game.must_pass(Team::Black));
+    assert!(game.must_pass(Team::White));
+    assert!(game.must_pass(Team::Red));
+    assert_eq!(
+      game.play(Move::Pass { team: Team::Black }),
+      Err(GameError::CannotPass)
+    );
+
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    assert_eq!(game.legal_moves(), [Move::Pass { team: Team::White }]);
+    game.play(Move::Pass { team: Team::White })?;
+    assert_eq!(game.turn(), Team::Black);
+
+    // Once neither team can place anything, nobody passes anymore.
+    let board = BoardBuilder::new(
+      "
+      w  C  b  B1
+      C  C  C  B1
+      w  C  B2 B2
+      W  C  B2 B2
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let mut game = Game::with_board(board);
+    for hand in game.hands.values_mut() {
+      hand.take(PieceKind::Tavern);
+      hand.take(PieceKind::Tavern);
+    }
+    assert_eq!(
+      game.play(Move::Pass { team: Team::Black }),
+      Err(GameError::GameOver)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_reset_with() -> Result<(), GameError> {
+    let mut game = started_game();
+    game.play(place(Team::Black, PieceKind::Castle, (0, 0)))?;
+    game.reset_with(Rules::default());
+    assert_eq!(game.phase(), Phase::Cathedral);
+    assert_eq!(game.turn(), Team::White);
+    assert_eq!(game.hand(Team::Black).remaining().len(), PIECES_PER_TEAM);
+    assert_eq!(game.hand(Team::None).remaining(), [PieceKind::Cathedral]);
+    assert_eq!(game.board().pieces().count(), 0);
+    Ok(())
+  }
+
+  #[test]
+  fn test_result() -> Result<(), GameError> {
+    let board = BoardBuilder::new(
+      "
+      B1 C  B2 B2
+      C  C  C  B2
+      B3 C  B4 B4
+      b  C  B4 B4
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let mut game = Game::with_board(board);
+    assert!(!game.is_over());
+    assert_eq!(game.result(), None);
+
+    game.play(place(Team::Black, PieceKind::Tavern, (3, 0)))?;
+    assert!(game.is_over());
+    assert_eq!(game.result(), Some(GameResult::Winner(Team::Black)));
+    game.reset_with(Rules::default());
+    assert!(!game.is_over());
+
+    let board = BoardBuilder::new(
+      "
+      B1 C  B2 B2
+      C  C  C  B2
+      B3 C  B4 B4
+      B5 C  B4 B4
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert_eq!(Game::with_board(board).result(), Some(GameResult::Draw));
+    Ok(())
+  }
+
+  #[test]
+  fn test_tie_break() -> Result<(), GameError> {
+    let game_with = |tie_break| {
+      let board = BoardBuilder::new(
+        "
+        B1 C  b  b
+        C  C  C  W1
+        B2 C  W2 W2
+        B2 C  W2 W2
+        ",
+      )
+      .build()
+      .expect("must be valid art");
+      let mut game = Game::with_board(board);
+      game.tie_break = tie_break;
+      let white_hand = game.hands.get_mut(&Team::White).expect("has hand");
+      white_hand.take(PieceKind::Stable);
+      game
+    };
+    let play_out = |game: &mut Game| -> Result<(), GameError> {
+      game.play(place(Team::Black, PieceKind::Tavern, (0, 2)))?;
+      game.play(Move::Pass { team: Team::White })?;
+      game.play(place(Team::Black, PieceKind::Tavern, (0, 3)))?;
+      Ok(())
+    };
+
+    let mut game = game_with(TieBreak::Shared);
+    play_out(&mut game)?;
+    assert!(game.is_over());
+    assert_eq!(game.result(), Some(GameResult::Draw));
+
+    let mut game = game_with(TieBreak::FirstToFinish);
+    play_out(&mut game)?;
+    assert_eq!(game.result(), Some(GameResult::Winner(Team::White)));
+    Ok(())
+  }
+
+  #[test]
+  fn test_tie_break_territory() {
+    let board = BoardBuilder::new(
+      "
+      w  C  b  B1
+      C  C  C  B1
+      w  C  B2 B2
+      W  C  B2 B2
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let mut game = Game::with_board(board);
+    for hand in game.hands.values_mut() {
+      hand.take(PieceKind::Tavern);
+      hand.take(PieceKind::Tavern);
+    }
+    assert!(game.is_over());
+    assert_eq!(game.result(), Some(GameResult::Draw));
+    game.tie_break = TieBreak::Territory;
+    assert_eq!(game.config().tie_break, TieBreak::Territory);
+    assert_eq!(game.result(), Some(GameResult::Winner(Team::White)));
+
+    let config = GameConfig {
+      rules: Rules {
+        region_connectivity: Connectivity::Diagonal,
+        ..Rules::default()
+      },
+      tie_break: TieBreak::FirstToFinish,
+      board_size: 8,
+      players: 3,
+      piece_set: PieceSetPreset::Mini,
+    };
+    assert_eq!(Game::with_config(config).config(), config);
+  }
+
+  #[test]
+  fn test_piece_set_presets() -> Result<(), GameError> {
+    let mut game = Game::with_config(GameConfig {
+      board_size: 6,
+      piece_set: PieceSetPreset::Mini,
+      ..GameConfig::default()
+    });
+    assert_eq!(game.hand(Team::White).remaining_squares(), 15);
+    game.play(place(Team::White, PieceKind::Cathedral, (1, 1)))?;
+    game.play(place(Team::Black, PieceKind::Abbey, (4, 3)))?;
+    assert_eq!(game.hand(Team::Black).remaining().len(), 5);
+    assert_eq!(game.score()[&Team::Black].unplaced_squares, 15 - 4);
+
+    let game = Game::with_config(GameConfig {
+      board_size: 14,
+      piece_set: PieceSetPreset::Extended,
+      ..GameConfig::default()
+    });
+    assert_eq!(
+      game.hand(Team::Black).remaining_squares() * 2 + CATHEDRAL_SQUARES,
+      14 * 14
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_multiplayer() -> Result<(), GameError> {
+    let mut game = Game::with_config(GameConfig {
+      players: 4,
+      ..GameConfig::default()
+    });
+    assert_eq!(game.players(), Team::PLAYERS);
+    assert_eq!(
+      game.hand(Team::Green).remaining().len(),
+      PieceKind::ALL.len() - 1
+    );
+    game.play(place(Team::White, PieceKind::Cathedral, (3, 3)))?;
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    assert_eq!(game.turn(), Team::Red);
+    let outcome = game.play("R:INN@a1^D".parse().expect("must be valid"))?;
+    assert_eq!(outcome.captured_pieces, [Piece::new_tavern(Team::Black)]);
+    assert_eq!(game.board().tile((0, 0).into()), Tile::Empty(Team::Red));
+    assert_eq!(
+      game.play(place(Team::Green, PieceKind::Tavern, (0, 0))),
+      Err(GameError::Board(BoardError::PieceOnEnemyTile(
+        (0, 0).into()
+      )))
+    );
+    game.play(place(Team::Green, PieceKind::Tavern, (9, 9)))?;
+    assert_eq!(game.turn(), Team::White);
+
+    game.play(Move::Resign { team: Team::Green })?;
+    assert_eq!(game.result(), Some(GameResult::Winner(Team::Red)));
+    Ok(())
+  }
+
+  #[test]
+  fn test_score_breakdown() -> Result<(), GameError> {
+    let mut game = started_game();
+    for team in [Team::White, Team::Black] {
+      let hand = game.hands.get_mut(&team).expect("team must have a hand");
+      for kind in PieceKind::for_team(team) {
+        if !matches!(kind, PieceKind::Tavern | PieceKind::Castle) {
+          hand.take(*kind);
+        }
+      }
+    }
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    game.play(place(Team::White, PieceKind::Tavern, (0, 1)))?;
+    game.play(place(Team::Black, PieceKind::Tavern, (9, 9)))?;
+    game.play(place(Team::White, PieceKind::Tavern, (1, 0)))?;
+
+    let breakdown = game
+      .score_breakdown(Team::White)
+      .expect("white team is playing");
+    assert_eq!(breakdown, ScoreBreakdown {
+      team: Team::White,
+      unplaced: vec![(PieceKind::Castle, 5)],
+      unplaced_squares: 5,
+      captured: vec![PieceKind::Tavern],
+      territory: 1,
+      scoring: Scoring::Unplaced,
+      penalty: 5,
+      tie_break: TieBreak::Shared,
+      passed_first: false,
+    });
+    assert_eq!(
+      breakdown.to_string(),
+      "Unplaced: Castle (5) = 5 squares\n\
+       Captured: Tavern\n\
+       Territory: 1 tiles\n\
+       Tie break: Shared"
+    );
+    let breakdown = game
+      .score_breakdown(Team::Black)
+      .expect("black team is playing");
+    assert_eq!(breakdown.unplaced, [
+      (PieceKind::Castle, 5),
+      (PieceKind::Tavern, 1)
+    ]);
+    assert!(breakdown.to_string().contains("Captured: none\n"));
+    assert_eq!(game.score_breakdown(Team::Red), None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_weighted_territory_scoring() -> Result<(), GameError> {
+    let weighted = GameConfig {
+      rules: Rules {
+        scoring: Scoring::WeightedTerritory { weight: 5 },
+        ..Rules::default()
+      },
+      ..GameConfig::default()
+    };
+    for (config, winner) in [
+      (GameConfig::default(), Team::Black),
+      (weighted, Team::White),
+    ] {
+      let mut game = Game::with_config(config);
+      game.play(place(Team::White, PieceKind::Cathedral, (3, 3)))?;
+      for team in [Team::White, Team::Black] {
+        let hand = game.hands.get_mut(&team).expect("team must have a hand");
+        for kind in PieceKind::for_team(team) {
+          let kept = match team {
+            Team::White => [PieceKind::Tavern, PieceKind::Castle].as_slice(),
+            _ => &[PieceKind::Tavern],
+          };
+          if !kept.contains(kind) {
+            hand.take(*kind);
+          }
+        }
+      }
+      game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+      game.play(place(Team::White, PieceKind::Tavern, (0, 1)))?;
+      game.play(place(Team::Black, PieceKind::Tavern, (9, 9)))?;
+      game.play(place(Team::White, PieceKind::Tavern, (1, 0)))?;
+      assert_eq!(game.decide(), GameResult::Winner(winner));
+    }
+
+    let mut game = Game::with_config(weighted);
+    game.play(place(Team::White, PieceKind::Cathedral, (3, 3)))?;
+    let breakdown = game
+      .score_breakdown(Team::White)
+      .expect("white team is playing");
+    assert_eq!(breakdown.penalty, SQUARES_PER_TEAM as isize);
+    assert!(breakdown.to_string().contains(&format!(
+      "Penalty: {SQUARES_PER_TEAM} - 0 x 5 = {SQUARES_PER_TEAM}\n"
+    )));
+    Ok(())
+  }
+
+  #[test]
+  fn test_handicap() -> Result<(), SetupError> {
+    let handicap = Handicap::new(Team::Black)
+      .without(PieceKind::Castle)
+      .with_placed(PieceKind::Inn, Rotation::DOWN, (0, 0).into());
+    let mut game = Game::with_handicap(GameConfig::default(), &handicap)?;
+    assert_eq!(game.phase(), Phase::Cathedral);
+    assert_eq!(
+      game.hand(Team::Black).remaining_squares(),
+      SQUARES_PER_TEAM - 5 - 3
+    );
+    assert_eq!(game.hand(Team::White).remaining_squares(), SQUARES_PER_TEAM);
+    assert_eq!(game.board().pieces().count(), 1);
+    game
+      .play(place(Team::White, PieceKind::Cathedral, (3, 3)))
+      .expect("cathedral must fit");
+    assert_eq!(game.turn(), Team::Black);
+
+    let config = GameConfig::default();
+    let graded = Handicap::graded(&config, Team::White, 2);
+    let game = Game::with_handicap(config, &graded)?;
+    assert_eq!(
+      game.hand(Team::White).remaining_squares(),
+      SQUARES_PER_TEAM - 5 - 5
+    );
+    let config = GameConfig {
+      piece_set: PieceSetPreset::Mini,
+      ..GameConfig::default()
+    };
+    let graded = Handicap::graded(&config, Team::White, 2);
+    let game = Game::with_handicap(config, &graded)?;
+    assert_eq!(game.hand(Team::White).remaining_squares(), 15 - 4 - 4);
+
+    let handicap = Handicap::new(Team::White)
+      .without(PieceKind::Castle)
+      .without(PieceKind::Castle);
+    assert_eq!(
+      Game::with_handicap(GameConfig::default(), &handicap).err(),
+      Some(SetupError::PieceNotInHand(PieceKind::Castle))
+    );
+    assert_eq!(
+      Game::with_handicap(GameConfig::default(), &Handicap::new(Team::Red))
+        .err(),
+      Some(SetupError::NotPlaying(Team::Red))
+    );
+    let handicap = Handicap::new(Team::White).with_placed(
+      PieceKind::Castle,
+      Rotation::UP,
+      (9, 9).into(),
+    );
+    assert!(matches!(
+      Game::with_handicap(GameConfig::default(), &handicap),
+      Err(SetupError::Board(BoardError::PieceOutOfBounds(_)))
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_is_safe_placement() -> Result<(), GameError> {
+    let board = BoardBuilder::new(
+      "
+      .  .  W1 . . .
+      W2 W2 .  . C .
+      .  .  .  C C C
+      .  .  .  . C .
+      .  .  .  . C .
+      .  .  .  . . .
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    let mut game = GameBuilder::new(board).build().expect("must be consistent");
+    assert!(!game.is_safe_placement(place(
+      Team::Black,
+      PieceKind::Tavern,
+      (0, 0)
+    ))?);
+    assert!(game.is_safe_placement(place(
+      Team::Black,
+      PieceKind::Tavern,
+      (3, 1)
+    ))?);
+    assert!(game.is_safe_placement(Move::Resign { team: Team::Black })?);
+    assert_eq!(
+      game.is_safe_placement(place(Team::White, PieceKind::Tavern, (3, 1))),
+      Err(GameError::NotYourTurn(Team::White))
+    );
+    assert!(game.history().is_empty());
+    assert_eq!(game.turn(), Team::Black);
+    assert_eq!(game.board().tile((0, 0).into()), Tile::Empty(Team::None));
+    assert!(game.redo().is_none());
+
+    let mv = place(Team::Black, PieceKind::Tavern, (3, 1));
+    game.play(mv)?;
+    game.undo();
+    game.is_safe_placement(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    assert_eq!(game.redo(), Some(mv));
+    Ok(())
+  }
+
+  #[test]
+  fn test_game_builder() -> Result<(), GameError> {
+    let art = "
+      . C . W
+      C C C .
+      . C . .
+      . C . B
+      ";
+    let board = || BoardBuilder::new(art).build().expect("must be valid art");
+    let game = GameBuilder::new(board())
+      .build()
+      .expect("must be consistent");
+    assert_eq!(game.phase(), Phase::Pieces);
+    assert_eq!(game.turn(), Team::Black);
+    assert_eq!(
+      game.hand(Team::White).remaining().len(),
+      PIECES_PER_TEAM - 1
+    );
+    assert!(game.hand(Team::None).is_empty());
+
+    let mut game = GameBuilder::new(board())
+      .hand(Team::White, &[PieceKind::Tavern])
+      .hand(Team::Black, &[])
+      .turn(Team::White)
+      .tie_break(TieBreak::Territory)
+      .build()
+      .expect("must be consistent");
+    assert_eq!(game.config().tie_break, TieBreak::Territory);
+    assert!(game.must_pass(Team::Black));
+    game.play(place(Team::White, PieceKind::Tavern, (2, 3)))?;
+    assert!(game.is_over());
+
+    assert_eq!(
+      GameBuilder::new(board())
+        .hand(Team::White, &[PieceKind::Tavern, PieceKind::Tavern])
+        .build()
+        .err(),
+      Some(SetupError::TooManyPieces(Team::White, PieceKind::Tavern))
+    );
+    assert_eq!(
+      GameBuilder::new(board()).turn(Team::None).build().err(),
+      Some(SetupError::InvalidTurn(Team::None))
+    );
+    assert_eq!(
+      GameBuilder::new(board()).turn(Team::Red).build().err(),
+      Some(SetupError::InvalidTurn(Team::Red))
+    );
+    assert_eq!(
+      GameBuilder::new(Board::with_size(4))
+        .turn(Team::Black)
+        .build()
+        .err(),
+      Some(SetupError::CathedralNotPlaced)
+    );
+    assert_eq!(
+      GameBuilder::new(board())
+        .hand(Team::Red, &[PieceKind::Tavern])
+        .build()
+        .err(),
+      Some(SetupError::NotPlaying(Team::Red))
+    );
+    let red = BoardBuilder::new(
+      "
+      . C . W
+      C C C .
+      . C . R
+      . C . B
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert_eq!(
+      GameBuilder::new(red).build().err(),
+      Some(SetupError::NotPlaying(Team::Red))
+    );
+
+    let mut broken = board();
+    broken.set_tile((2, 2).into(), Tile::Occupied(Team::Black));
+    assert_eq!(
+      GameBuilder::new(broken).build().err(),
+      Some(SetupError::InvalidBoard(InvalidBoardError::TileMismatch(
+        (2, 2).into()
+      )))
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_undo_redo() -> Result<(), GameError> {
+    let mut game = Game::new();
+    assert_eq!(game.undo(), None);
+    let moves = [
+      place(Team::White, PieceKind::Cathedral, (3, 3)),
+      place(Team::Black, PieceKind::Tavern, (0, 0)),
+      place(Team::White, PieceKind::Tavern, (0, 1)),
+      place(Team::Black, PieceKind::Tavern, (9, 9)),
+      place(Team::White, PieceKind::Tavern, (1, 0)),
+    ];
+    let mut states = Vec::new();
+    for mv in moves {
+      states.push((game.hands.clone(), game.board.to_string(), game.turn));
+      game.play(mv)?;
+    }
+    assert_eq!(game.history().len(), moves.len());
+    assert_eq!(game.history()[4].mv(), moves[4]);
+    assert_eq!(game.history()[4].outcome().captured_pieces, [
+      Piece::new_tavern(Team::Black)
+    ]);
+    assert_eq!(game.board.score_for(Team::White).captured_pieces, 1);
+
+    for (mv, state) in moves.into_iter().zip(states).rev() {
+      assert_eq!(game.undo(), Some(mv));
+      assert_eq!(
+        (game.hands.clone(), game.board.to_string(), game.turn),
+        state
+      );
+    }
+    assert_eq!(game.phase(), Phase::Cathedral);
+    assert_eq!(game.board.cathedral_status(), CathedralStatus::InBox);
+    assert_eq!(game.board.score_for(Team::White).captured_pieces, 0);
+
+    for mv in moves {
+      assert_eq!(game.redo(), Some(mv));
+    }
+    assert_eq!(game.redo(), None);
+    assert_eq!(game.board.tile((0, 0).into()), Tile::Empty(Team::White));
+    assert_eq!(game.board.score_for(Team::White).captured_pieces, 1);
+
+    game.undo();
+    game.play(place(Team::White, PieceKind::Tavern, (5, 0)))?;
+    assert_eq!(game.redo(), None);
+
+    game.play(Move::Resign { team: Team::Black })?;
+    assert!(game.is_over());
+    game.undo();
+    assert!(!game.is_over());
+    Ok(())
+  }
+
+  #[test]
+  fn test_phased_game() {
+    let game = Game::start(GameConfig::default());
+    let CathedralPlacement::Rejected(game, e) =
+      game.place_cathedral(Rotation::UP, (9, 9).into())
+    else {
+      panic!("cathedral must not fit");
+    };
+    assert!(matches!(
+      e,
+      GameError::Board(BoardError::PieceOutOfBounds(_))
+    ));
+    let CathedralPlacement::Placed(game) =
+      game.place_cathedral(Rotation::UP, (3, 3).into())
+    else {
+      panic!("cathedral must fit");
+    };
+    assert_eq!(game.phase(), Phase::Pieces);
+
+    let Progress::Rejected(game, e) =
+      game.play(place(Team::White, PieceKind::Tavern, (0, 0)))
+    else {
+      panic!("it must be black team's turn");
+    };
+    assert_eq!(e, GameError::NotYourTurn(Team::White));
+    let Progress::Playing(game) =
+      game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))
+    else {
+      panic!("game must go on");
+    };
+    let Progress::Finished(game) =
+      game.play(Move::Resign { team: Team::White })
+    else {
+      panic!("game must be over");
+    };
+    assert_eq!(game.result(), GameResult::Resignation(Team::White));
+    assert_eq!(game.into_any_phase().history().len(), 3);
+
+    let board = BoardBuilder::new(
+      "
+      w  C  b  B1
+      C  C  C  B1
+      w  C  B2 B2
+      W  C  B2 B2
+      ",
+    )
+    .build()
+    .expect("must be valid art");
+    assert!(matches!(
+      Game::with_board(board).into_phased(),
+      PhasedGame::Playing(_)
+    ));
+    assert!(matches!(
+      Game::new().into_phased(),
+      PhasedGame::Cathedral(_)
+    ));
+  }
+
+  #[test]
+  fn test_resign() -> Result<(), GameError> {
+    let mut game = started_game();
+    assert_eq!(
+      game.play(Move::Resign { team: Team::None }),
+      Err(GameError::NotPlaying(Team::None))
+    );
+    assert_eq!(
+      game.play(Move::Resign { team: Team::Red }),
+      Err(GameError::NotPlaying(Team::Red))
+    );
+    assert_eq!(
+      game.play(place(Team::Red, PieceKind::Tavern, (0, 0))),
+      Err(GameError::NotPlaying(Team::Red))
+    );
+    assert!(!game.is_over());
+    game.play(Move::Resign { team: Team::White })?;
+    assert!(game.is_over());
+    let result = game.result().expect("game must be over");
+    assert_eq!(result, GameResult::Resignation(Team::White));
+    assert_eq!(result.winner(), Some(Team::Black));
+    assert_eq!(
+      game.play(place(Team::Black, PieceKind::Tavern, (0, 0))),
+      Err(GameError::GameOver)
+    );
+
+    game.reset_with(Rules::default());
+    assert!(!game.is_over());
+    Ok(())
+  }
+
+  #[test]
+  fn test_draw_offer() -> Result<(), GameError> {
+    let mut game = started_game();
+    assert_eq!(
+      game.play(Move::AcceptDraw { team: Team::White }),
+      Err(GameError::NoDrawOffer)
+    );
+    assert_eq!(
+      game.play(Move::OfferDraw { team: Team::Green }),
+      Err(GameError::NotPlaying(Team::Green))
+    );
+    assert_eq!(game.draw_offer(), None);
+    game.play(Move::OfferDraw { team: Team::Black })?;
+    assert_eq!(game.draw_offer(), Some(Team::Black));
+    assert_eq!(
+      game.play(Move::AcceptDraw { team: Team::Black }),
+      Err(GameError::NoDrawOffer)
+    );
+    assert_eq!(
+      game.play(Move::AcceptDraw { team: Team::Red }),
+      Err(GameError::NotPlaying(Team::Red))
+    );
+    assert_eq!(
+      game.play(Move::AcceptDraw { team: Team::None }),
+      Err(GameError::NotPlaying(Team::None))
+    );
+    game.play(place(Team::Black, PieceKind::Tavern, (0, 0)))?;
+    assert_eq!(game.draw_offer(), Some(Team::Black));
+    game.play(place(Team::White, PieceKind::Tavern, (9, 9)))?;
+    assert_eq!(game.draw_offer(), None);
+    assert_eq!(
+      game.play(Move::AcceptDraw { team: Team::White }),
+      Err(GameError::NoDrawOffer)
+    );
+
+    game.play(Move::OfferDraw { team: Team::White })?;
+    game.play(Move::AcceptDraw { team: Team::Black })?;
+    assert!(game.is_over());
+    assert_eq!(game.result(), Some(GameResult::Agreed));
+    assert_eq!(GameResult::Agreed.winner(), None);
+    assert_eq!(
+      game.play(Move::Resign { team: Team::Black }),
+      Err(GameError::GameOver)
+    );
+
+    game.undo();
+    assert!(!game.is_over());
+    assert_eq!(game.draw_offer(), Some(Team::White));
+    game.undo();
+    assert_eq!(game.draw_offer(), None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_from_moves() -> Result<(), ReplayError> {
+    let moves = [
+      place(Team::White, PieceKind::Cathedral, (3, 3)),
+      place(Team::Black, PieceKind::Tavern, (0, 0)),
+      place(Team::White, PieceKind::Tavern, (0, 1)),
+      place(Team::Black, PieceKind::Tavern, (9, 9)),
+      place(Team::White, PieceKind::Tavern, (1, 0)),
+    ];
+    let game = Game::from_moves(&moves)?;
+    assert_eq!(game.history().len(), moves.len());
+    assert_eq!(game.board().captured_by(Team::White), [PieceKind::Tavern]);
+    assert_eq!(game.turn(), Team::Black);
+
+    let notation = game.to_notation();
+    assert!(notation.starts_with("W:CATHEDRAL@d4^U B:TAVERN@a1^U"));
+    let replayed = Game::from_notation(&notation)?;
+    assert_eq!(replayed.board().to_string(), game.board().to_string());
+    assert_eq!(replayed.to_notation(), notation);
+
+    let mut illegal = moves.to_vec();
+    illegal.insert(2, place(Team::Black, PieceKind::Tavern, (5, 5)));
+    assert_eq!(
+      Game::from_moves(&illegal).err(),
+      Some(ReplayError::IllegalMove {
+        index: 2,
+        error: GameError::NotYourTurn(Team::Black)
+      })
+    );
+    assert_eq!(
+      Game::from_notation("W:CATHEDRAL@d4^U B:PASS B:INN").err(),
+      Some(ReplayError::InvalidMove {
+        index: 2,
+        error: ParseMoveError::InvalidFormat
+      })
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_move_ordering() {
+    let mut moves = vec![
+      Move::Resign { team: Team::White },
+      Move::Pass { team: Team::Black },
+      place(Team::Black, PieceKind::Tavern, (0, 0)),
+      place(Team::White, PieceKind::Stable, (0, 0)),
+      place(Team::White, PieceKind::Tavern, (1, 0)),
+      place(Team::White, PieceKind::Tavern, (0, 1)),
+    ];
+    moves.sort();
+    assert_eq!(moves, [
+      place(Team::White, PieceKind::Tavern, (0, 1)),
+      place(Team::White, PieceKind::Tavern, (1, 0)),
+      place(Team::White, PieceKind::Stable, (0, 0)),
+      place(Team::Black, PieceKind::Tavern, (0, 0)),
+      Move::Pass { team: Team::Black },
+      Move::Resign { team: Team::White },
+    ]);
+  }
+
+  #[test]
+  fn test_move_notation() -> Result<(), ParseMoveError> {
+    let academy = Move::Place {
+      team: Team::White,
+      kind: PieceKind::Academy,
+      rotation: Rotation::RIGHT,
+      position: (3, 2).into(),
+    };
+    assert_eq!(academy.to_string(), "W:ACADEMY@c4^R");
+    assert_eq!("W:ACADEMY@c4^R".parse::<Move>()?, academy);
+    assert_eq!("B:PASS".parse::<Move>()?, Move::Pass { team: Team::Black });
+
+    let moves = [
+      place(Team::None, PieceKind::Cathedral, (9, 0)),
+      place(Team::Black, PieceKind::Tavern, (12, 27)),
+      Move::Resign { team: Team::White },
+      Move::OfferDraw { team: Team::Black },
+      Move::AcceptDraw { team: Team::White },
+    ];
+    for mv in moves {
+      assert_eq!(mv.to_string().parse::<Move>()?, mv);
+    }
+    assert_eq!(moves[0].to_string(), "N:CATHEDRAL@a10^U");
+    assert_eq!(moves[1].to_string(), "B:TAVERN@ab13^U");
+    assert_eq!(moves[3].to_string(), "B:OFFER_DRAW");
+    Ok(())
+  }
+
+  #[test]
+  fn test_move_notation_errors() {
+    let parse = |s: &str| s.parse::<Move>().err();
+    assert_eq!(parse("ACADEMY@c4^R"), Some(ParseMoveError::InvalidFormat));
+    assert_eq!(parse("W:ACADEMY@c4"), Some(ParseMoveError::InvalidFormat));
+    assert_eq!(parse("X:PASS"), Some(ParseMoveError::InvalidTeam));
+    assert_eq!(
+      parse("W:Academy@c4^R"),
+      Some(ParseMoveError::InvalidPiece("Academy".into()))
+    );
+    for square in ["c0", "c", "4", "C4", "c4x"] {
+      assert_eq!(
+        parse(&format!("W:ACADEMY@{square}^R")),
+        Some(ParseMoveError::InvalidSquare(square.into()))
+      );
+    }
+    assert_eq!(
+      parse("W:ACADEMY@c4^X"),
+      Some(ParseMoveError::InvalidRotation)
+    );
+  }
+}
@@ -0,0 +1,200 @@
+use std::{
+  collections::hash_map::RandomState,
+  fmt::Display,
+  hash::{BuildHasher, Hash, Hasher},
+  str::FromStr,
+  sync::atomic::{AtomicU64, Ordering},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::ParseGameIdError;
+
+/// Default adjectives used by `GameId::generate`. The list is curated by hand
+/// to stay free of offensive combinations.
+pub const ADJECTIVES: &[&str] = &[
+  "amber", "ancient", "arched", "autumn", "bold", "brave", "bright", "calm",
+  "carved", "clever", "copper", "crimson", "curious", "daring", "dawn",
+  "eager", "early", "elder", "fair", "gentle", "gilded", "golden", "grand",
+  "green", "happy", "hidden", "honest", "humble", "ivory", "jolly", "keen",
+  "kind", "lively", "lucky", "marble", "merry", "mighty", "misty", "noble",
+  "olive", "patient", "plain", "proud", "quiet", "rapid", "royal", "rustic",
+  "sandy", "silent", "silver", "simple", "sly", "solid", "spring", "steady",
+  "stone", "sunny", "swift", "tall", "tidy", "vivid", "warm", "wise", "young",
+];
+
+/// Default nouns used by `GameId::generate`. The list is curated by hand to
+/// stay free of offensive combinations.
+pub const NOUNS: &[&str] = &[
+  "abbey", "academy", "alley", "arch", "bell", "bridge", "castle", "chapel",
+  "choir", "cloister", "column", "court", "crypt", "dome", "door", "fountain",
+  "gable", "garden", "gate", "hall", "harbor", "hearth", "inn", "keep",
+  "lantern", "library", "manor", "market", "mill", "moat", "nave", "orchard",
+  "palace", "pillar", "plaza", "porch", "quarry", "rampart", "roof", "square",
+  "stable", "stair", "statue", "steeple", "street", "tavern", "temple",
+  "terrace", "tower", "town", "vault", "village", "wall", "well", "window",
+  "yard", "beacon", "belfry", "citadel", "forge", "granary", "meadow", "spire",
+  "turret",
+];
+
+/// Exclusive upper bound of the numeric part of generated ids.
+const NUMBER_BOUND: u64 = 10_000;
+
+/// Short human-readable game identifier in `word-word-number` form,
+/// e.g. `amber-tower-4821`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GameId {
+  adjective: String,
+  noun: String,
+  number: u16,
+}
+
+impl GameId {
+  /// Generates a new id from the default word lists.
+  pub fn generate() -> Self {
+    Self::generate_from(ADJECTIVES, NOUNS)
+  }
+
+  /// Generates a new id picking words from given `adjectives` and `nouns`.
+  /// Panics if either list is empty.
+  pub fn generate_from(adjectives: &[&str], nouns: &[&str]) -> Self {
+    assert!(
+      !adjectives.is_empty() && !nouns.is_empty(),
+      "word lists must not be empty"
+    );
+    let mut entropy = entropy();
+    let mut pick = |bound: u64| {
+      let value = entropy % bound;
+      entropy /= bound;
+      value as usize
+    };
+    Self {
+      adjective: adjectives[pick(adjectives.len() as u64)].to_owned(),
+      noun: nouns[pick(nouns.len() as u64)].to_owned(),
+      number: pick(NUMBER_BOUND) as u16,
+    }
+  }
+
+  pub fn adjective(&self) -> &str {
+    &self.adjective
+  }
+
+  pub fn noun(&self) -> &str {
+    &self.noun
+  }
+
+  pub fn number(&self) -> u16 {
+    self.number
+  }
+}
+
+/// Returns 64 bits of entropy mixed from a randomly seeded hasher, current
+/// time and a process-wide counter, so ids generated in a tight loop differ.
+fn entropy() -> u64 {
+  static COUNTER: AtomicU64 = AtomicU64::new(0);
+  let mut hasher = RandomState::new().build_hasher();
+  COUNTER.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos())
+    .unwrap_or_default()
+    .hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Returns `true` if `word` can be a part of game id.
+fn is_valid_word(word: &str) -> bool {
+  !word.is_empty() && word.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+impl Display for GameId {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}-{}-{}", self.adjective, self.noun, self.number)
+  }
+}
+
+impl FromStr for GameId {
+  type Err = ParseGameIdError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.split('-');
+    let (Some(adjective), Some(noun), Some(number), None) =
+      (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+      return Err(ParseGameIdError::InvalidFormat);
+    };
+    if !is_valid_word(adjective) || !is_valid_word(noun) {
+      return Err(ParseGameIdError::InvalidWord);
+    }
+    let number = match number.parse::<u16>() {
+      Ok(n) if u64::from(n) < NUMBER_BOUND && !number.starts_with('+') => n,
+      _ => return Err(ParseGameIdError::InvalidNumber),
+    };
+    Ok(Self {
+      adjective: adjective.to_owned(),
+      noun: noun.to_owned(),
+      number,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use super::*;
+
+  #[test]
+  fn test_generate_format() {
+    let id = GameId::generate();
+    assert!(ADJECTIVES.contains(&id.adjective()));
+    assert!(NOUNS.contains(&id.noun()));
+    assert!(u64::from(id.number()) < NUMBER_BOUND);
+
+    let id = GameId::generate_from(&["red"], &["door"]);
+    assert_eq!(id.adjective(), "red");
+    assert_eq!(id.noun(), "door");
+  }
+
+  #[test]
+  fn test_generate_distinct() {
+    let ids = (0..100).map(|_| GameId::generate()).collect::<HashSet<_>>();
+    assert!(ids.len() > 95);
+  }
+
+  #[test]
+  fn test_display_from_str_round_trip() {
+    let id = GameId::generate();
+    assert_eq!(id.to_string().parse::<GameId>(), Ok(id));
+
+    let id = "amber-tower-7".parse::<GameId>().expect("must be valid id");
+    assert_eq!(id.to_string(), "amber-tower-7");
+  }
+
+  #[test]
+  fn test_from_str_errors() {
+    assert_eq!(
+      "amber-tower".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidFormat)
+    );
+    assert_eq!(
+      "amber-tower-1-2".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidFormat)
+    );
+    assert_eq!(
+      "Amber-tower-1".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidWord)
+    );
+    assert_eq!(
+      "amber--1".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidWord)
+    );
+    assert_eq!(
+      "amber-tower-10000".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidNumber)
+    );
+    assert_eq!(
+      "amber-tower-+1".parse::<GameId>(),
+      Err(ParseGameIdError::InvalidNumber)
+    );
+  }
+}
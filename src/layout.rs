@@ -0,0 +1,237 @@
+use std::fmt::Display;
+
+use crate::consts::MAX_PIECE_DIMENSION;
+
+/// Tiles a piece covers within its bounding box. Cells are packed into bits
+/// row by row, so layouts are `Copy` and can be built and rotated in const
+/// context without allocating.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Layout {
+  rows: u8,
+  cols: u8,
+  bits: u16,
+}
+
+impl Layout {
+  /// Returns a layout with given rows of cells, `true` for occupied ones.
+  /// Panics if there are more than `MAX_PIECE_DIMENSION` rows or columns.
+  pub const fn new<const R: usize, const C: usize>(
+    cells: [[bool; C]; R],
+  ) -> Self {
+    let mut layout = Self::empty(R, C);
+    let mut row = 0;
+    while row < R {
+      let mut col = 0;
+      while col < C {
+        if cells[row][col] {
+          layout = layout.with(row, col);
+        }
+        col += 1;
+      }
+      row += 1;
+    }
+    layout
+  }
+
+  /// Returns a layout of given size with no occupied cells.
+  /// Panics if size exceeds `MAX_PIECE_DIMENSION` on either axis.
+  pub const fn empty(rows: usize, cols: usize) -> Self {
+    assert!(
+      rows <= MAX_PIECE_DIMENSION && cols <= MAX_PIECE_DIMENSION,
+      "layout must fit into piece dimensions"
+    );
+    Self {
+      rows: rows as u8,
+      cols: cols as u8,
+      bits: 0,
+    }
+  }
+
+  /// Returns this layout with cell at `row` and `col` occupied.
+  /// Panics if the cell is out of bounds.
+  pub const fn with(self, row: usize, col: usize) -> Self {
+    assert!(row < self.rows as usize && col < self.cols as usize);
+    Self {
+      bits: self.bits | Self::bit(row, col),
+      ..self
+    }
+  }
+
+  /// Returns number of rows and columns.
+  pub const fn dim(self) -> (usize, usize) {
+    (self.rows as usize, self.cols as usize)
+  }
+
+  /// Returns `true` if cell at `row` and `col` is occupied. Cells out of
+  /// bounds are never occupied.
+  pub const fn get(self, row: usize, col: usize) -> bool {
+    row < self.rows as usize
+      && col < self.cols as usize
+      && self.bits & Self::bit(row, col) != 0
+  }
+
+  /// Returns number of occupied cells.
+  pub const fn count(self) -> usize {
+    self.bits.count_ones() as usize
+  }
+
+  /// Returns this layout rotated 90 degrees clockwise.
+  pub const fn rotated_clockwise(self) -> Self {
+    let (rows, cols) = self.dim();
+    let mut layout = Self::empty(cols, rows);
+    let mut row = 0;
+    while row < cols {
+      let mut col = 0;
+      while col < rows {
+        if self.get(rows - 1 - col, row) {
+          layout = layout.with(row, col);
+        }
+        col += 1;
+      }
+      row += 1;
+    }
+    layout
+  }
+
+  /// Returns this layout rotated 90 degrees counterclockwise.
+  pub const fn rotated_counterclockwise(self) -> Self {
+    let (rows, cols) = self.dim();
+    let mut layout = Self::empty(cols, rows);
+    let mut row = 0;
+    while row < cols {
+      let mut col = 0;
+      while col < rows {
+        if self.get(col, cols - 1 - row) {
+          layout = layout.with(row, col);
+        }
+        col += 1;
+      }
+      row += 1;
+    }
+    layout
+  }
+
+  /// Returns this layout flipped left to right.
+  pub const fn mirrored(self) -> Self {
+    let (rows, cols) = self.dim();
+    let mut layout = Self::empty(rows, cols);
+    let mut row = 0;
+    while row < rows {
+      let mut col = 0;
+      while col < cols {
+        if self.get(row, cols - 1 - col) {
+          layout = layout.with(row, col);
+        }
+        col += 1;
+      }
+      row += 1;
+    }
+    layout
+  }
+
+  /// Returns iterator of occupied cells' `(row, col)` coordinates, row by
+  /// row.
+  pub fn occupied_cells_iter(self) -> impl Iterator<Item = (usize, usize)> {
+    let (rows, cols) = self.dim();
+    (0..rows)
+      .flat_map(move |row| (0..cols).map(move |col| (row, col)))
+      .filter(move |(row, col)| self.get(*row, *col))
+  }
+
+  const fn bit(row: usize, col: usize) -> u16 {
+    1 << (row * MAX_PIECE_DIMENSION + col)
+  }
+}
+
+impl Display for Layout {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let (rows, cols) = self.dim();
+    write!(f, "[")?;
+    for row in 0..rows {
+      if row > 0 {
+        write!(f, ",\n ")?;
+      }
+      write!(f, "[")?;
+      for col in 0..cols {
+        if col > 0 {
+          write!(f, ", ")?;
+        }
+        write!(f, "{}", self.get(row, col))?;
+      }
+      write!(f, "]")?;
+    }
+    write!(f, "]")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ABBEY: Layout = Layout::new([
+    [false, true, true], //
+    [true, true, false],
+  ]);
+
+  #[test]
+  fn test_layout() {
+    assert_eq!(ABBEY.dim(), (2, 3));
+    assert_eq!(ABBEY.count(), 4);
+    assert!(ABBEY.get(0, 1));
+    assert!(!ABBEY.get(0, 0));
+    assert!(!ABBEY.get(5, 5));
+    assert_eq!(ABBEY.occupied_cells_iter().collect::<Vec<_>>(), [
+      (0, 1),
+      (0, 2),
+      (1, 0),
+      (1, 1)
+    ]);
+    assert_eq!(
+      ABBEY,
+      Layout::empty(2, 3)
+        .with(0, 1)
+        .with(0, 2)
+        .with(1, 0)
+        .with(1, 1)
+    );
+  }
+
+  #[test]
+  fn test_layout_transforms() {
+    const ROTATED: Layout = ABBEY.rotated_clockwise();
+    assert_eq!(
+      ROTATED,
+      Layout::new([
+        [true, false], //
+        [true, true],
+        [false, true],
+      ])
+    );
+    assert_eq!(ROTATED.rotated_counterclockwise(), ABBEY);
+    assert_eq!(
+      ABBEY.rotated_clockwise().rotated_clockwise(),
+      ABBEY.rotated_counterclockwise().rotated_counterclockwise()
+    );
+    assert_eq!(
+      ABBEY.mirrored(),
+      Layout::new([
+        [true, true, false], //
+        [false, true, true],
+      ])
+    );
+  }
+
+  #[test]
+  fn test_layout_display() {
+    assert_eq!(
+      ABBEY.to_string(),
+      "[[false, true, true],\n [true, true, false]]"
+    );
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_layout_too_big() {
+    Layout::empty(5, 1);
+  }
+}
@@ -1,25 +1,60 @@
-use std::fmt::Display;
+//! Rules model of the Cathedral board game.
+//!
+//! The core model, including boards, pieces, games and errors, follows
+//! semantic versioning. Experimental subsystems, such as rendering,
+//! fog-of-war views and the blitz variant, are only built with the
+//! `unstable` feature and may change in any release.
 
-use ndarray::Array2;
+use std::fmt::Display;
 
+#[cfg(feature = "unstable")]
+pub mod blitz;
 pub mod board;
+pub mod consts;
 pub mod error;
+pub mod game;
+pub mod game_id;
+pub mod layout;
 pub mod piece;
+pub mod piece_set;
 pub mod position;
+#[cfg(feature = "unstable")]
+pub mod render;
+mod rng;
+pub mod rules;
+pub mod testing;
+#[cfg(feature = "unstable")]
+pub mod view;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Teams are ordered as declared: white, black, red, green, then no team.
+/// Red and green teams only take part in games of three or four teams.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Team {
   White,
   Black,
+  Red,
+  Green,
   None,
 }
 
 impl Team {
+  /// Teams that can play, in order they take turns.
+  pub const PLAYERS: [Self; 4] =
+    [Self::White, Self::Black, Self::Red, Self::Green];
+
+  /// Returns the other team of a two-team game. Other teams have no single
+  /// opponent and stay as they are.
+  pub fn opponent(self) -> Self {
+    match self {
+      Self::White => Self::Black,
+      Self::Black => Self::White,
+      _ => self,
+    }
+  }
+
+  /// Returns `true` if both teams play and are different ones.
   pub fn is_opposing_team(&self, team: &Self) -> bool {
-    matches!(
-      (*self, *team),
-      (Self::White, Self::Black) | (Self::Black, Self::White)
-    )
+    self != team && *self != Self::None && *team != Self::None
   }
 }
 
@@ -28,6 +63,8 @@ impl Display for Team {
     write!(f, "{}", match self {
       Self::White => "░░",
       Self::Black => "██",
+      Self::Red => "▓▓",
+      Self::Green => "▚▚",
       Self::None => "╳╳",
     })
   }
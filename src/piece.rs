@@ -1,7 +1,9 @@
-use std::{fmt::Display, marker::PhantomData};
+use std::{fmt::Display, marker::PhantomData, ops::Add};
 
-use ndarray::{array, Axis};
+use consts::PIECES_PER_TEAM;
+use layout::Layout;
 use position::Position;
+use rules::PieceSetPreset;
 
 use super::*;
 
@@ -15,12 +17,14 @@ impl PieceState for Placed {}
 pub enum Released {}
 impl PieceState for Released {}
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// Rotations are ordered as declared, which is by clockwise quarter turns:
+/// up, right, down, left.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Rotation {
   UP,
+  RIGHT,
   DOWN,
   LEFT,
-  RIGHT,
 }
 
 impl Rotation {
@@ -41,33 +45,386 @@ impl Rotation {
       Self::RIGHT => Self::UP,
     }
   }
+
+  /// Returns number of clockwise quarter turns this rotation is made of.
+  pub const fn quarter_turns(self) -> u8 {
+    match self {
+      Self::UP => 0,
+      Self::RIGHT => 1,
+      Self::DOWN => 2,
+      Self::LEFT => 3,
+    }
+  }
+
+  /// Returns clockwise rotation angle in degrees.
+  pub const fn into_degrees(self) -> u16 {
+    self.quarter_turns() as u16 * 90
+  }
+
+  /// Returns rotation that cancels this one out.
+  pub fn inverse(self) -> Self {
+    Self::from(4 - self.quarter_turns())
+  }
+}
+
+impl From<u8> for Rotation {
+  /// Converts number of clockwise quarter turns into rotation.
+  fn from(value: u8) -> Self {
+    match value % 4 {
+      0 => Self::UP,
+      1 => Self::RIGHT,
+      2 => Self::DOWN,
+      _ => Self::LEFT,
+    }
+  }
+}
+
+impl Add for Rotation {
+  type Output = Self;
+
+  /// Composes two rotations.
+  fn add(self, rhs: Self) -> Self::Output {
+    Self::from(self.quarter_turns() + rhs.quarter_turns())
+  }
+}
+
+/// Kinds are ordered as declared, which is by size, the same as in
+/// `PieceKind::ALL`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum PieceKind {
+  Tavern,
+  Stable,
+  Inn,
+  Bridge,
+  Square,
+  Manor,
+  Abbey,
+  Academy,
+  Infirmary,
+  Castle,
+  Tower,
+  Cathedral,
+}
+
+impl PieceKind {
+  /// All piece kinds, ordered by size.
+  pub const ALL: [Self; 12] = [
+    Self::Tavern,
+    Self::Stable,
+    Self::Inn,
+    Self::Bridge,
+    Self::Square,
+    Self::Manor,
+    Self::Abbey,
+    Self::Academy,
+    Self::Infirmary,
+    Self::Castle,
+    Self::Tower,
+    Self::Cathedral,
+  ];
+
+  /// Kinds of pieces each team starts with, including duplicates.
+  const TEAM_SET: [Self; PIECES_PER_TEAM] = [
+    Self::Tavern,
+    Self::Tavern,
+    Self::Stable,
+    Self::Stable,
+    Self::Inn,
+    Self::Inn,
+    Self::Bridge,
+    Self::Square,
+    Self::Manor,
+    Self::Abbey,
+    Self::Academy,
+    Self::Infirmary,
+    Self::Castle,
+    Self::Tower,
+  ];
+
+  /// Kinds of pieces each team starts with in games of three or four teams:
+  /// one piece of every kind but the cathedral.
+  const REDUCED_SET: [Self; 11] = [
+    Self::Tavern,
+    Self::Stable,
+    Self::Inn,
+    Self::Bridge,
+    Self::Square,
+    Self::Manor,
+    Self::Abbey,
+    Self::Academy,
+    Self::Infirmary,
+    Self::Castle,
+    Self::Tower,
+  ];
+
+  /// Kinds of pieces of the mini set, for a 6×6 board.
+  const MINI_SET: [Self; 6] = [
+    Self::Tavern,
+    Self::Tavern,
+    Self::Stable,
+    Self::Inn,
+    Self::Square,
+    Self::Abbey,
+  ];
+
+  /// Kinds of pieces of the extended set, for a 14×14 board: the standard
+  /// set twice and one more tavern.
+  const EXTENDED_SET: [Self; 2 * PIECES_PER_TEAM + 1] = {
+    let mut kinds = [Self::Tavern; 2 * PIECES_PER_TEAM + 1];
+    let mut i = 0;
+    while i < 2 * PIECES_PER_TEAM {
+      kinds[i] = Self::TEAM_SET[i % PIECES_PER_TEAM];
+      i += 1;
+    }
+    kinds
+  };
+
+  /// Returns kinds of pieces given `team` starts with in a game of two
+  /// teams, including duplicates. `Team::None` owns only the cathedral. Use
+  /// `Piece::new` to build the pieces themselves, since abbey and academy
+  /// layouts are mirrored for black and green teams.
+  pub fn for_team(team: Team) -> &'static [Self] {
+    match team {
+      Team::None => &[Self::Cathedral],
+      _ => &Self::TEAM_SET,
+    }
+  }
+
+  /// Returns kinds of pieces given `team` starts with in a game of
+  /// `players` teams that uses `preset` sets. With three or four teams the
+  /// standard preset gives each of them a reduced set holding a single piece
+  /// of every kind.
+  pub fn for_team_in(
+    team: Team,
+    preset: PieceSetPreset,
+    players: usize,
+  ) -> &'static [Self] {
+    match (team, preset) {
+      (Team::None, _) => &[Self::Cathedral],
+      (_, PieceSetPreset::Standard) if players > 2 => &Self::REDUCED_SET,
+      (_, PieceSetPreset::Standard) => &Self::TEAM_SET,
+      (_, PieceSetPreset::Mini) => &Self::MINI_SET,
+      (_, PieceSetPreset::Extended) => &Self::EXTENDED_SET,
+    }
+  }
+
+  /// Returns human-readable name of this kind.
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::Tavern => "Tavern",
+      Self::Stable => "Stable",
+      Self::Inn => "Inn",
+      Self::Bridge => "Bridge",
+      Self::Square => "Square",
+      Self::Manor => "Manor",
+      Self::Abbey => "Abbey",
+      Self::Academy => "Academy",
+      Self::Infirmary => "Infirmary",
+      Self::Castle => "Castle",
+      Self::Tower => "Tower",
+      Self::Cathedral => "Cathedral",
+    }
+  }
+
+  /// Returns unique two-letter code of this kind, used in notation tables.
+  pub const fn code(self) -> &'static str {
+    match self {
+      Self::Tavern => "TA",
+      Self::Stable => "ST",
+      Self::Inn => "IN",
+      Self::Bridge => "BR",
+      Self::Square => "SQ",
+      Self::Manor => "MA",
+      Self::Abbey => "AB",
+      Self::Academy => "AC",
+      Self::Infirmary => "IF",
+      Self::Castle => "CS",
+      Self::Tower => "TO",
+      Self::Cathedral => "CA",
+    }
+  }
+
+  /// Returns kind with given two-letter `code`, if there is one.
+  pub fn from_code(code: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|kind| kind.code() == code)
+  }
+
+  /// Returns `true` if pieces of this kind can't be turned into their mirror
+  /// image by rotation. Teams get mirrored layouts of chiral pieces, so a
+  /// chiral piece must never be reflected, only rotated.
+  pub const fn is_chiral(self) -> bool {
+    matches!(self, Self::Abbey | Self::Academy)
+  }
+
+  /// Returns number of tiles a piece of this kind occupies.
+  pub const fn square_count(self) -> usize {
+    match self {
+      Self::Tavern => 1,
+      Self::Stable => 2,
+      Self::Inn | Self::Bridge => 3,
+      Self::Square | Self::Manor | Self::Abbey => 4,
+      Self::Academy | Self::Infirmary | Self::Castle | Self::Tower => 5,
+      Self::Cathedral => 6,
+    }
+  }
+}
+
+/// Look of a piece drawn with `Piece::to_art`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ArtStyle {
+  /// Occupied tiles are full blocks two characters wide.
+  #[default]
+  Blocks,
+  /// Box-drawing lines trace the piece's border.
+  Outline,
+  /// Plain ASCII: `#` for occupied tiles and `.` for empty ones.
+  Ascii,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Piece<S: PieceState> {
+  kind: PieceKind,
   team: Team,
-  layout: Array2<bool>,
+  layout: Layout,
   position: Position,
   rotation: Rotation,
   _state: PhantomData<S>,
 }
 
 impl<S: PieceState> Piece<S> {
+  pub fn kind(&self) -> PieceKind {
+    self.kind
+  }
+
   pub fn team(&self) -> Team {
     self.team
   }
+
+  /// Returns rotation of this piece relative to the layout it was created
+  /// with. Placing a piece and picking it up again keeps its orientation.
+  pub fn orientation(&self) -> Rotation {
+    self.rotation
+  }
+
+  /// Returns size of piece's bounding box as position.
+  pub fn size(&self) -> Position {
+    Position::from(self.layout.dim())
+  }
+
+  /// Draws the piece alone, in its current rotation, as lines of text in
+  /// given `style`. Lines are of equal width and not terminated by a line
+  /// break.
+  pub fn to_art(&self, style: ArtStyle) -> String {
+    let (rows, cols) = self.layout.dim();
+    let lines = match style {
+      ArtStyle::Blocks | ArtStyle::Ascii => {
+        let (filled, empty) = match style {
+          ArtStyle::Blocks => ("██", "  "),
+          _ => ("#", "."),
+        };
+        (0..rows)
+          .map(|row| {
+            (0..cols)
+              .map(|col| {
+                if self.layout.get(row, col) {
+                  filled
+                } else {
+                  empty
+                }
+              })
+              .collect::<String>()
+          })
+          .collect::<Vec<_>>()
+      }
+      ArtStyle::Outline => self.outline_lines(),
+    };
+    lines.join("\n")
+  }
+
+  /// Returns lines of the piece's border drawn on a lattice of tile corners,
+  /// with lines of corners and edges between lines of tiles.
+  fn outline_lines(&self) -> Vec<String> {
+    let (rows, cols) = self.layout.dim();
+    let get = |row: usize, col: usize| {
+      row < rows && col < cols && self.layout.get(row, col)
+    };
+    // Edge to the right of corner `(row, col)`, between two rows of tiles.
+    let horizontal = |row: usize, col: usize| {
+      let above = row > 0 && get(row - 1, col);
+      above != get(row, col)
+    };
+    // Edge below corner `(row, col)`, between two columns of tiles.
+    let vertical = |row: usize, col: usize| {
+      let left = col > 0 && get(row, col - 1);
+      left != get(row, col)
+    };
+    let mut lines = Vec::new();
+    for row in 0..=rows {
+      let mut line = String::new();
+      for col in 0..=cols {
+        let up = row > 0 && vertical(row - 1, col);
+        let down = row < rows && vertical(row, col);
+        let left = col > 0 && horizontal(row, col - 1);
+        let right = col < cols && horizontal(row, col);
+        line.push(match (up, down, left, right) {
+          (false, true, false, true) => '┌',
+          (false, true, true, false) => '┐',
+          (true, false, false, true) => '└',
+          (true, false, true, false) => '┘',
+          (true, true, false, false) => '│',
+          (false, false, true, true) => '─',
+          (true, true, true, true) => '┼',
+          _ => ' ',
+        });
+        if col < cols {
+          line.push(if right { '─' } else { ' ' });
+        }
+      }
+      lines.push(line);
+      if row < rows {
+        let line = (0..=cols)
+          .flat_map(|col| {
+            let edge = if vertical(row, col) { '│' } else { ' ' };
+            [edge, ' '].into_iter().take(if col < cols { 2 } else { 1 })
+          })
+          .collect();
+        lines.push(line);
+      }
+    }
+    lines
+  }
 }
 
 impl Piece<Released> {
+  /// Returns a piece of given `kind` that belongs to `team`. The cathedral
+  /// always belongs to `Team::None`, whatever `team` is passed.
+  pub const fn new(kind: PieceKind, team: Team) -> Self {
+    match kind {
+      PieceKind::Tavern => Self::new_tavern(team),
+      PieceKind::Stable => Self::new_stable(team),
+      PieceKind::Inn => Self::new_inn(team),
+      PieceKind::Bridge => Self::new_bridge(team),
+      PieceKind::Square => Self::new_square(team),
+      PieceKind::Manor => Self::new_manor(team),
+      PieceKind::Abbey => Self::new_abbey(team),
+      PieceKind::Academy => Self::new_academy(team),
+      PieceKind::Infirmary => Self::new_infirmary(team),
+      PieceKind::Castle => Self::new_castle(team),
+      PieceKind::Tower => Self::new_tower(team),
+      PieceKind::Cathedral => Self::new_cathedral(),
+    }
+  }
+
   /// Returns a piece with this layout:
   /// <pre>
   /// []
   /// </pre>
-  pub fn new_tavern(team: Team) -> Self {
+  pub const fn new_tavern(team: Team) -> Self {
     Self {
+      kind: PieceKind::Tavern,
       team,
-      layout: array![[true]],
-      position: Position::default(),
+      layout: Layout::new([[true]]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -78,14 +435,15 @@ impl Piece<Released> {
   /// []
   /// []
   /// </pre>
-  pub fn new_stable(team: Team) -> Self {
+  pub const fn new_stable(team: Team) -> Self {
     Self {
+      kind: PieceKind::Stable,
       team,
-      layout: array![
+      layout: Layout::new([
         [true], //
-        [true]
-      ],
-      position: Position::default(),
+        [true],
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -96,14 +454,15 @@ impl Piece<Released> {
   /// [][]
   /// []
   /// </pre>
-  pub fn new_inn(team: Team) -> Self {
+  pub const fn new_inn(team: Team) -> Self {
     Self {
+      kind: PieceKind::Inn,
       team,
-      layout: array![
+      layout: Layout::new([
         [true, true], //
-        [true, false]
-      ],
-      position: Position::default(),
+        [true, false],
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -115,15 +474,16 @@ impl Piece<Released> {
   /// []
   /// []
   /// </pre>
-  pub fn new_bridge(team: Team) -> Self {
+  pub const fn new_bridge(team: Team) -> Self {
     Self {
+      kind: PieceKind::Bridge,
       team,
-      layout: array![
+      layout: Layout::new([
         [true], //
         [true],
-        [true]
-      ],
-      position: Position::default(),
+        [true],
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -134,14 +494,15 @@ impl Piece<Released> {
   /// [][]
   /// [][]
   /// </pre>
-  pub fn new_square(team: Team) -> Self {
+  pub const fn new_square(team: Team) -> Self {
     Self {
+      kind: PieceKind::Square,
       team,
-      layout: array![
+      layout: Layout::new([
         [true, true], //
-        [true, true]
-      ],
-      position: Position::default(),
+        [true, true],
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -152,14 +513,15 @@ impl Piece<Released> {
   /// [][][]
   ///   []
   /// </pre>
-  pub fn new_manor(team: Team) -> Self {
+  pub const fn new_manor(team: Team) -> Self {
     Self {
+      kind: PieceKind::Manor,
       team,
-      layout: array![
+      layout: Layout::new([
         [true, true, true], //
-        [false, true, false]
-      ],
-      position: Position::default(),
+        [false, true, false],
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -167,25 +529,26 @@ impl Piece<Released> {
 
   /// Returns a piece with this layout:
   /// <pre>
-  /// white:  black:
-  ///   [][]  [][]
-  /// [][]      [][]
+  /// white, red:  black, green:
+  ///   [][]       [][]
+  /// [][]           [][]
   /// </pre>
-  pub fn new_abbey(team: Team) -> Self {
+  pub const fn new_abbey(team: Team) -> Self {
     Self {
+      kind: PieceKind::Abbey,
       team,
       layout: match team {
-        Team::White => array![
+        Team::White | Team::Red => Layout::new([
           [false, true, true], //
-          [true, true, false]
-        ],
-        Team::Black => array![
+          [true, true, false],
+        ]),
+        Team::Black | Team::Green => Layout::new([
           [true, true, false], //
           [false, true, true],
-        ],
-        _ => unreachable!("a piece can be either black or white"),
+        ]),
+        _ => panic!("a piece must belong to a playing team"),
       },
-      position: Position::default(),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -193,28 +556,29 @@ impl Piece<Released> {
 
   /// Returns a piece with this layout:
   /// <pre>
-  /// white:  black:
-  ///     []  []
-  /// [][][]  [][][]
-  ///   []      []
+  /// white, red:  black, green:
+  ///     []       []
+  /// [][][]       [][][]
+  ///   []           []
   /// </pre>
-  pub fn new_academy(team: Team) -> Self {
+  pub const fn new_academy(team: Team) -> Self {
     Self {
+      kind: PieceKind::Academy,
       team,
       layout: match team {
-        Team::White => array![
+        Team::White | Team::Red => Layout::new([
           [false, false, true], //
           [true, true, true],
           [false, true, false],
-        ],
-        Team::Black => array![
+        ]),
+        Team::Black | Team::Green => Layout::new([
           [true, false, false], //
           [true, true, true],
           [false, true, false],
-        ],
-        _ => unreachable!("a piece can be either black or white"),
+        ]),
+        _ => panic!("a piece must belong to a playing team"),
       },
-      position: Position::default(),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -226,15 +590,16 @@ impl Piece<Released> {
   /// [][][]
   ///   []
   /// </pre>
-  pub fn new_infirmary(team: Team) -> Self {
+  pub const fn new_infirmary(team: Team) -> Self {
     Self {
+      kind: PieceKind::Infirmary,
       team,
-      layout: array![
+      layout: Layout::new([
         [false, true, false], //
         [true, true, true],
         [false, true, false],
-      ],
-      position: Position::default(),
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -245,14 +610,15 @@ impl Piece<Released> {
   /// [][][]
   /// []  []
   /// </pre>
-  pub fn new_castle(team: Team) -> Self {
+  pub const fn new_castle(team: Team) -> Self {
     Self {
+      kind: PieceKind::Castle,
       team,
-      layout: array![
+      layout: Layout::new([
         [true, true, true], //
         [true, false, true],
-      ],
-      position: Position::default(),
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -264,15 +630,16 @@ impl Piece<Released> {
   /// [][]
   /// []
   /// </pre>
-  pub fn new_tower(team: Team) -> Self {
+  pub const fn new_tower(team: Team) -> Self {
     Self {
+      kind: PieceKind::Tower,
       team,
-      layout: array![
+      layout: Layout::new([
         [false, true, true], //
         [true, true, false],
         [true, false, false],
-      ],
-      position: Position::default(),
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
@@ -285,46 +652,83 @@ impl Piece<Released> {
   ///   []
   ///   []
   /// </pre>
-  pub fn new_cathedral() -> Self {
+  pub const fn new_cathedral() -> Self {
     let team = Team::None;
     Self {
+      kind: PieceKind::Cathedral,
       team,
-      layout: array![
+      layout: Layout::new([
         [false, true, false], //
         [true, true, true],
         [false, true, false],
         [false, true, false],
-      ],
-      position: Position::default(),
+      ]),
+      position: Position { x: 0, y: 0 },
       rotation: Rotation::UP,
       _state: PhantomData,
     }
   }
 
+  /// Returns a piece of `team` whose layout, in some rotation, equals to
+  /// given `layout`, or `None` if there is no such piece. Symmetric pieces
+  /// get the smallest clockwise rotation that matches.
+  pub(crate) fn from_layout(team: Team, layout: Layout) -> Option<Self> {
+    PieceKind::for_team(team).iter().find_map(|kind| {
+      let mut piece = Self::new(*kind, team);
+      (0..4).find_map(|_| {
+        let found = (piece.layout == layout).then(|| piece.clone());
+        piece.rotate_clockwise();
+        found
+      })
+    })
+  }
+
   /// Emulates placing a piece down at given position.
   /// Changes its position and state to `Placed`.
   pub fn placed_at(self, position: Position) -> Piece<Placed> {
     Piece {
+      kind: self.kind,
       team: self.team,
       layout: self.layout,
       position,
-      rotation: Rotation::UP,
+      rotation: self.rotation,
       _state: PhantomData,
     }
   }
 
   /// Rotates piece 90 degrees clockwise.
   pub fn rotate_clockwise(&mut self) {
-    self.layout.swap_axes(0, 1);
-    self.layout.invert_axis(Axis(1));
-    self.rotation = self.rotation.clone().rotated_clockwise();
+    self.layout = self.layout.rotated_clockwise();
+    self.rotation = self.rotation.rotated_clockwise();
   }
 
   /// Rotates piece 90 degrees counterclockwise.
   pub fn rotate_counterclockwise(&mut self) {
-    self.layout.swap_axes(0, 1);
-    self.layout.invert_axis(Axis(0));
-    self.rotation = self.rotation.clone().rotated_counterclockwise();
+    self.layout = self.layout.rotated_counterclockwise();
+    self.rotation = self.rotation.rotated_counterclockwise();
+  }
+
+  /// Rotates piece clockwise until its orientation equals to `rotation`.
+  pub fn rotate_to(&mut self, rotation: Rotation) {
+    while self.rotation != rotation {
+      self.rotate_clockwise();
+    }
+  }
+
+  /// Returns orientations in which this piece has distinct layouts, starting
+  /// with the current one and going clockwise.
+  pub fn distinct_orientations(&self) -> Vec<Rotation> {
+    let mut piece = self.clone();
+    let mut layouts = Vec::new();
+    let mut orientations = Vec::new();
+    for _ in 0..4 {
+      if !layouts.contains(&piece.layout) {
+        layouts.push(piece.layout);
+        orientations.push(piece.rotation);
+      }
+      piece.rotate_clockwise();
+    }
+    orientations
   }
 
   /// Returns iterator of tiles' local coordinates that this piece occupies.
@@ -336,9 +740,8 @@ impl Piece<Released> {
   ) -> impl Iterator<Item = Position> + '_ {
     self
       .layout
-      .indexed_iter()
-      .filter(|(_, occupied)| **occupied)
-      .map(move |(coords, _)| position + coords.into())
+      .occupied_cells_iter()
+      .map(move |cell| position + cell.into())
   }
 }
 
@@ -350,10 +753,11 @@ impl Piece<Placed> {
   /// Emulates picking a piece up. Changes its state to `Released`.
   pub fn released(self) -> Piece<Released> {
     Piece {
+      kind: self.kind,
       team: self.team,
       layout: self.layout,
       position: Position::default(),
-      rotation: Rotation::UP,
+      rotation: self.rotation,
       _state: PhantomData,
     }
   }
@@ -363,9 +767,14 @@ impl Piece<Placed> {
   pub fn occupied_positions_iter(&self) -> impl Iterator<Item = Position> + '_ {
     self
       .layout
-      .indexed_iter()
-      .filter(|(_, occupied)| **occupied)
-      .map(move |(coords, _)| self.position() + coords.into())
+      .occupied_cells_iter()
+      .map(move |cell| self.position() + cell.into())
+  }
+}
+
+impl Display for PieceKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", self.name())
   }
 }
 
@@ -379,197 +788,498 @@ impl<S: PieceState> Display for Piece<S> {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_to_art() {
+    let mut inn = Piece::new_inn(Team::White);
+    assert_eq!(inn.to_art(ArtStyle::Ascii), "##\n#.");
+    assert_eq!(inn.to_art(ArtStyle::Blocks), "████\n██  ");
+    assert_eq!(
+      inn.to_art(ArtStyle::Outline),
+      "┌───┐\n\
+       │   │\n\
+       │ ┌─┘\n\
+       │ │  \n\
+       └─┘  "
+    );
+    inn.rotate_clockwise();
+    assert_eq!(inn.to_art(ArtStyle::Ascii), "##\n.#");
+
+    let white = Piece::new_abbey(Team::White).to_art(ArtStyle::Ascii);
+    let black = Piece::new_abbey(Team::Black).to_art(ArtStyle::Ascii);
+    assert_ne!(white, black);
+    assert!(white
+      .lines()
+      .chain(black.lines())
+      .all(|line| line.len() == 3));
+  }
+
+  #[test]
+  fn test_ordering() {
+    let mut kinds = PieceKind::ALL;
+    kinds.reverse();
+    kinds.sort();
+    assert_eq!(kinds, PieceKind::ALL);
+    assert!(PieceKind::Tavern < PieceKind::Cathedral);
+    let mut rotations = [
+      Rotation::LEFT,
+      Rotation::DOWN,
+      Rotation::RIGHT,
+      Rotation::UP,
+    ];
+    rotations.sort();
+    assert_eq!(rotations.map(Rotation::quarter_turns), [0, 1, 2, 3]);
+    assert!(Rotation::RIGHT < Rotation::DOWN);
+  }
+
+  #[test]
+  fn test_rotation_arithmetic() {
+    let rotations = [
+      Rotation::UP,
+      Rotation::RIGHT,
+      Rotation::DOWN,
+      Rotation::LEFT,
+    ];
+    for (i, rotation) in rotations.into_iter().enumerate() {
+      assert_eq!(Rotation::from(i as u8), rotation);
+      assert_eq!(Rotation::from(i as u8 + 4), rotation);
+      assert_eq!(rotation.into_degrees(), i as u16 * 90);
+      assert_eq!(rotation + rotation.inverse(), Rotation::UP);
+      assert_eq!(rotation + Rotation::UP, rotation);
+      assert_eq!(rotation + Rotation::RIGHT, rotation.rotated_clockwise());
+      assert_eq!(
+        rotation + Rotation::LEFT,
+        rotation.rotated_counterclockwise()
+      );
+    }
+    assert_eq!(Rotation::RIGHT.inverse(), Rotation::LEFT);
+    assert_eq!(Rotation::DOWN.inverse(), Rotation::DOWN);
+    assert_eq!(Rotation::DOWN + Rotation::LEFT, Rotation::RIGHT);
+  }
+
+  #[test]
+  fn test_orientation() {
+    let mut manor = Piece::new_manor(Team::White);
+    assert_eq!(manor.orientation(), Rotation::UP);
+    manor.rotate_clockwise();
+    manor.rotate_clockwise();
+    manor.rotate_clockwise();
+    assert_eq!(manor.orientation(), Rotation::LEFT);
+    manor.rotate_counterclockwise();
+    assert_eq!(manor.orientation(), Rotation::DOWN);
+
+    let layout = manor.layout;
+    let manor = manor.placed_at((1, 1).into());
+    assert_eq!(manor.orientation(), Rotation::DOWN);
+    let manor = manor.released();
+    assert_eq!(manor.orientation(), Rotation::DOWN);
+    assert_eq!(manor.layout, layout);
+  }
+
+  #[test]
+  fn test_rotate_to() {
+    let mut tower = Piece::new_tower(Team::White);
+    let mut rotated = tower.clone();
+    rotated.rotate_counterclockwise();
+    tower.rotate_to(Rotation::LEFT);
+    assert_eq!(tower, rotated);
+    tower.rotate_to(Rotation::LEFT);
+    assert_eq!(tower, rotated);
+  }
+
+  #[test]
+  fn test_distinct_orientations() {
+    let count =
+      |kind| Piece::new(kind, Team::White).distinct_orientations().len();
+    assert_eq!(count(PieceKind::Tavern), 1);
+    assert_eq!(count(PieceKind::Square), 1);
+    assert_eq!(count(PieceKind::Stable), 2);
+    assert_eq!(count(PieceKind::Bridge), 2);
+    assert_eq!(count(PieceKind::Abbey), 2);
+    assert_eq!(count(PieceKind::Infirmary), 1);
+    assert_eq!(count(PieceKind::Inn), 4);
+    assert_eq!(count(PieceKind::Cathedral), 4);
+
+    let mut stable = Piece::new_stable(Team::White);
+    stable.rotate_clockwise();
+    assert_eq!(stable.distinct_orientations(), [
+      Rotation::RIGHT,
+      Rotation::DOWN
+    ]);
+  }
+
+  #[test]
+  fn test_piece_kinds() {
+    assert_eq!(
+      PieceKind::ALL
+        .iter()
+        .collect::<std::collections::HashSet<_>>()
+        .len(),
+      PieceKind::ALL.len()
+    );
+    for kind in PieceKind::ALL {
+      assert_eq!(PieceKind::from_code(kind.code()), Some(kind));
+      assert_eq!(Piece::new(kind, Team::Black).kind(), kind);
+    }
+    assert_eq!(PieceKind::from_code("XX"), None);
+    assert_eq!(PieceKind::Infirmary.to_string(), "Infirmary");
+
+    assert_eq!(PieceKind::for_team(Team::White).len(), PIECES_PER_TEAM);
+    assert_eq!(PieceKind::for_team(Team::Black).len(), PIECES_PER_TEAM);
+    assert!(!PieceKind::for_team(Team::White).contains(&PieceKind::Cathedral));
+    assert_eq!(PieceKind::for_team(Team::None), [PieceKind::Cathedral]);
+
+    assert_eq!(
+      Piece::new(PieceKind::Abbey, Team::Black),
+      Piece::new_abbey(Team::Black)
+    );
+    assert_ne!(
+      Piece::new(PieceKind::Abbey, Team::White).layout,
+      Piece::new(PieceKind::Abbey, Team::Black).layout
+    );
+  }
+
+  #[test]
+  fn test_chirality() {
+    /// Returns layouts of all four rotations of `piece`.
+    fn rotations(mut piece: Piece<Released>) -> Vec<Layout> {
+      (0..4)
+        .map(|_| {
+          piece.rotate_clockwise();
+          piece.layout
+        })
+        .collect()
+    }
+
+    for kind in PieceKind::ALL {
+      let white_rotations = rotations(Piece::new(kind, Team::White));
+      let mirrored = Piece::new(kind, Team::White).layout.mirrored();
+      assert_eq!(kind.is_chiral(), !white_rotations.contains(&mirrored));
+
+      let black = Piece::new(kind, Team::Black);
+      assert_eq!(
+        kind.is_chiral(),
+        !white_rotations.contains(&black.layout),
+        "{kind} layouts must be mirrored for black team only if chiral"
+      );
+    }
+  }
+
+  #[test]
+  fn test_const_pieces() {
+    const ACADEMY: Piece<Released> =
+      Piece::new(PieceKind::Academy, Team::Black);
+    assert_eq!(ACADEMY, Piece::new_academy(Team::Black));
+    assert_eq!(ACADEMY.layout.count(), PieceKind::Academy.square_count());
+  }
+
+  #[test]
+  fn test_from_layout() {
+    let mut academy = Piece::new_academy(Team::Black);
+    academy.rotate_counterclockwise();
+    assert_eq!(
+      Piece::from_layout(Team::Black, academy.layout),
+      Some(academy.clone())
+    );
+    assert_eq!(Piece::from_layout(Team::White, academy.layout), None);
+
+    let cathedral = Piece::new_cathedral();
+    assert_eq!(
+      Piece::from_layout(Team::None, cathedral.layout),
+      Some(cathedral)
+    );
+    assert_eq!(
+      Piece::from_layout(Team::White, Layout::new([[true, false]])),
+      None
+    );
+  }
+
+  #[test]
+  fn test_square_count() {
+    let pieces = [
+      Piece::new_tavern(Team::White),
+      Piece::new_stable(Team::White),
+      Piece::new_inn(Team::White),
+      Piece::new_bridge(Team::White),
+      Piece::new_square(Team::White),
+      Piece::new_manor(Team::White),
+      Piece::new_abbey(Team::White),
+      Piece::new_academy(Team::Black),
+      Piece::new_infirmary(Team::Black),
+      Piece::new_castle(Team::Black),
+      Piece::new_tower(Team::Black),
+      Piece::new_cathedral(),
+    ];
+    for piece in pieces {
+      assert_eq!(
+        piece.kind().square_count(),
+        piece.occupied_positions_iter(Position::default()).count()
+      );
+    }
+  }
+
   #[test]
   fn test_clockwise_rotation() {
     let mut tavern = Piece::new_tavern(Team::White);
     tavern.rotate_clockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_clockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_clockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_clockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
 
     let mut stable = Piece::new_stable(Team::White);
     stable.rotate_clockwise();
-    assert_eq!(stable.layout, array![[true, true]]);
+    assert_eq!(stable.layout, Layout::new([[true, true]]));
     stable.rotate_clockwise();
-    assert_eq!(stable.layout, array![[true], [true]]);
+    assert_eq!(stable.layout, Layout::new([[true], [true]]));
     stable.rotate_clockwise();
-    assert_eq!(stable.layout, array![[true, true]]);
+    assert_eq!(stable.layout, Layout::new([[true, true]]));
     stable.rotate_clockwise();
-    assert_eq!(stable.layout, array![[true], [true]]);
+    assert_eq!(stable.layout, Layout::new([[true], [true]]));
 
     let mut inn = Piece::new_inn(Team::White);
     inn.rotate_clockwise();
-    assert_eq!(inn.layout, array![
-      [true, true], //
-      [false, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, true], //
+        [false, true]
+      ])
+    );
     inn.rotate_clockwise();
-    assert_eq!(inn.layout, array![
-      [false, true], //
-      [true, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [false, true], //
+        [true, true]
+      ])
+    );
     inn.rotate_clockwise();
-    assert_eq!(inn.layout, array![
-      [true, false], //
-      [true, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, false], //
+        [true, true]
+      ])
+    );
     inn.rotate_clockwise();
-    assert_eq!(inn.layout, array![
-      [true, true], //
-      [true, false]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, true], //
+        [true, false]
+      ])
+    );
 
     let mut manor = Piece::new_manor(Team::White);
     manor.rotate_clockwise();
-    assert_eq!(manor.layout, array![
-      [false, true], //
-      [true, true],
-      [false, true]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [false, true], //
+        [true, true],
+        [false, true]
+      ])
+    );
     manor.rotate_clockwise();
-    assert_eq!(manor.layout, array![
-      [false, true, false], //
-      [true, true, true]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [false, true, false], //
+        [true, true, true]
+      ])
+    );
     manor.rotate_clockwise();
-    assert_eq!(manor.layout, array![
-      [true, false], //
-      [true, true],
-      [true, false]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [true, false], //
+        [true, true],
+        [true, false]
+      ])
+    );
     manor.rotate_clockwise();
-    assert_eq!(manor.layout, array![
-      [true, true, true], //
-      [false, true, false]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [true, true, true], //
+        [false, true, false]
+      ])
+    );
 
     let mut cathedral = Piece::new_cathedral();
     cathedral.rotate_clockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, false, true, false], //
-      [true, true, true, true],
-      [false, false, true, false]
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, false, true, false], //
+        [true, true, true, true],
+        [false, false, true, false]
+      ])
+    );
     cathedral.rotate_clockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false], //
-      [false, true, false],
-      [true, true, true],
-      [false, true, false],
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false], //
+        [false, true, false],
+        [true, true, true],
+        [false, true, false],
+      ])
+    );
     cathedral.rotate_clockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false, false], //
-      [true, true, true, true],
-      [false, true, false, false]
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false, false], //
+        [true, true, true, true],
+        [false, true, false, false]
+      ])
+    );
     cathedral.rotate_clockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false], //
-      [true, true, true],
-      [false, true, false],
-      [false, true, false],
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false], //
+        [true, true, true],
+        [false, true, false],
+        [false, true, false],
+      ])
+    );
   }
 
   #[test]
   fn test_counterclockwise_rotation() {
     let mut tavern = Piece::new_tavern(Team::White);
     tavern.rotate_counterclockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_counterclockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_counterclockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
     tavern.rotate_counterclockwise();
-    assert_eq!(tavern.layout, array![[true]]);
+    assert_eq!(tavern.layout, Layout::new([[true]]));
 
     let mut stable = Piece::new_stable(Team::White);
     stable.rotate_counterclockwise();
-    assert_eq!(stable.layout, array![[true, true]]);
+    assert_eq!(stable.layout, Layout::new([[true, true]]));
     stable.rotate_counterclockwise();
-    assert_eq!(stable.layout, array![[true], [true]]);
+    assert_eq!(stable.layout, Layout::new([[true], [true]]));
     stable.rotate_counterclockwise();
-    assert_eq!(stable.layout, array![[true, true]]);
+    assert_eq!(stable.layout, Layout::new([[true, true]]));
     stable.rotate_counterclockwise();
-    assert_eq!(stable.layout, array![[true], [true]]);
+    assert_eq!(stable.layout, Layout::new([[true], [true]]));
 
     let mut inn = Piece::new_inn(Team::White);
     inn.rotate_counterclockwise();
-    assert_eq!(inn.layout, array![
-      [true, false], //
-      [true, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, false], //
+        [true, true]
+      ])
+    );
     inn.rotate_counterclockwise();
-    assert_eq!(inn.layout, array![
-      [false, true], //
-      [true, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [false, true], //
+        [true, true]
+      ])
+    );
     inn.rotate_counterclockwise();
-    assert_eq!(inn.layout, array![
-      [true, true], //
-      [false, true]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, true], //
+        [false, true]
+      ])
+    );
     inn.rotate_counterclockwise();
-    assert_eq!(inn.layout, array![
-      [true, true], //
-      [true, false]
-    ]);
+    assert_eq!(
+      inn.layout,
+      Layout::new([
+        [true, true], //
+        [true, false]
+      ])
+    );
 
     let mut manor = Piece::new_manor(Team::White);
     manor.rotate_counterclockwise();
-    assert_eq!(manor.layout, array![
-      [true, false], //
-      [true, true],
-      [true, false]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [true, false], //
+        [true, true],
+        [true, false]
+      ])
+    );
     manor.rotate_counterclockwise();
-    assert_eq!(manor.layout, array![
-      [false, true, false], //
-      [true, true, true]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [false, true, false], //
+        [true, true, true]
+      ])
+    );
     manor.rotate_counterclockwise();
-    assert_eq!(manor.layout, array![
-      [false, true], //
-      [true, true],
-      [false, true]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [false, true], //
+        [true, true],
+        [false, true]
+      ])
+    );
     manor.rotate_counterclockwise();
-    assert_eq!(manor.layout, array![
-      [true, true, true], //
-      [false, true, false]
-    ]);
+    assert_eq!(
+      manor.layout,
+      Layout::new([
+        [true, true, true], //
+        [false, true, false]
+      ])
+    );
 
     let mut cathedral = Piece::new_cathedral();
     cathedral.rotate_counterclockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false, false], //
-      [true, true, true, true],
-      [false, true, false, false]
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false, false], //
+        [true, true, true, true],
+        [false, true, false, false]
+      ])
+    );
     cathedral.rotate_counterclockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false], //
-      [false, true, false],
-      [true, true, true],
-      [false, true, false],
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false], //
+        [false, true, false],
+        [true, true, true],
+        [false, true, false],
+      ])
+    );
     cathedral.rotate_counterclockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, false, true, false], //
-      [true, true, true, true],
-      [false, false, true, false]
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, false, true, false], //
+        [true, true, true, true],
+        [false, false, true, false]
+      ])
+    );
     cathedral.rotate_counterclockwise();
-    assert_eq!(cathedral.layout, array![
-      [false, true, false], //
-      [true, true, true],
-      [false, true, false],
-      [false, true, false],
-    ]);
+    assert_eq!(
+      cathedral.layout,
+      Layout::new([
+        [false, true, false], //
+        [true, true, true],
+        [false, true, false],
+        [false, true, false],
+      ])
+    );
   }
 }
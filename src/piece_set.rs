@@ -0,0 +1,154 @@
+use crate::{
+  piece::{Piece, PieceKind, Released},
+  rules::PieceSetPreset,
+  Team,
+};
+
+/// Pieces a team has in hand, not yet on the board.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PieceSet {
+  team: Team,
+  kinds: Vec<PieceKind>,
+  /// Kinds the set holds when full.
+  full: &'static [PieceKind],
+}
+
+impl PieceSet {
+  /// Returns a full set of pieces given `team` starts with. `Team::None`
+  /// gets the cathedral.
+  pub fn new(team: Team) -> Self {
+    Self::with_preset(team, PieceSetPreset::Standard, 2)
+  }
+
+  /// Returns a full set of pieces given `team` starts with in a game of
+  /// `players` teams that uses `preset` sets.
+  pub fn with_preset(
+    team: Team,
+    preset: PieceSetPreset,
+    players: usize,
+  ) -> Self {
+    let full = PieceKind::for_team_in(team, preset, players);
+    Self {
+      team,
+      kinds: full.to_vec(),
+      full,
+    }
+  }
+
+  /// Returns a set of `team` with no pieces in it.
+  pub fn empty(team: Team) -> Self {
+    Self {
+      kinds: Vec::new(),
+      ..Self::new(team)
+    }
+  }
+
+  /// Puts every piece of the team's full set back, keeping allocated
+  /// memory.
+  pub fn refill(&mut self) {
+    self.kinds.clear();
+    self.kinds.extend_from_slice(self.full);
+  }
+
+  pub fn team(&self) -> Team {
+    self.team
+  }
+
+  /// Returns kinds of pieces left in the set, including duplicates.
+  pub fn remaining(&self) -> &[PieceKind] {
+    &self.kinds
+  }
+
+  /// Returns total number of squares pieces left in the set cover.
+  pub fn remaining_squares(&self) -> usize {
+    self.kinds.iter().map(|kind| kind.square_count()).sum()
+  }
+
+  pub fn contains(&self, kind: PieceKind) -> bool {
+    self.kinds.contains(&kind)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.kinds.is_empty()
+  }
+
+  /// Takes a piece of given `kind` out of the set.
+  /// Returns `None` if there is no such piece left.
+  pub fn take(&mut self, kind: PieceKind) -> Option<Piece<Released>> {
+    let index = self.kinds.iter().position(|k| *k == kind)?;
+    self.kinds.remove(index);
+    Some(Piece::new(kind, self.team))
+  }
+
+  /// Puts `piece` back into the set. Panics if it belongs to another team.
+  pub fn return_piece(&mut self, piece: Piece<Released>) {
+    assert_eq!(piece.team(), self.team, "piece must belong to set's team");
+    self.kinds.push(piece.kind());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::consts::{PIECES_PER_TEAM, SQUARES_PER_TEAM};
+
+  #[test]
+  fn test_piece_set() {
+    let mut set = PieceSet::new(Team::White);
+    assert_eq!(set.remaining().len(), PIECES_PER_TEAM);
+    assert_eq!(set.remaining_squares(), SQUARES_PER_TEAM);
+
+    let castle = set.take(PieceKind::Castle).expect("must have castle");
+    assert_eq!(castle, Piece::new_castle(Team::White));
+    assert!(!set.contains(PieceKind::Castle));
+    assert_eq!(set.take(PieceKind::Castle), None);
+    assert_eq!(set.remaining_squares(), SQUARES_PER_TEAM - 5);
+
+    set.take(PieceKind::Tavern);
+    assert!(set.contains(PieceKind::Tavern));
+    set.return_piece(castle);
+    assert_eq!(set.remaining().len(), PIECES_PER_TEAM - 1);
+    assert_eq!(set.remaining_squares(), SQUARES_PER_TEAM - 1);
+
+    set.refill();
+    assert_eq!(set.remaining_squares(), SQUARES_PER_TEAM);
+  }
+
+  #[test]
+  fn test_preset_sets() {
+    let mut set = PieceSet::with_preset(Team::Red, PieceSetPreset::Standard, 4);
+    assert_eq!(set.remaining().len(), PieceKind::ALL.len() - 1);
+    assert_eq!(
+      set.take(PieceKind::Tavern),
+      Some(Piece::new_tavern(Team::Red))
+    );
+    assert!(!set.contains(PieceKind::Tavern));
+    set.refill();
+    assert!(set.contains(PieceKind::Tavern));
+    assert_eq!(
+      PieceSet::with_preset(Team::White, PieceSetPreset::Standard, 2),
+      PieceSet::new(Team::White)
+    );
+
+    let mini = PieceSet::with_preset(Team::Black, PieceSetPreset::Mini, 2);
+    assert_eq!(mini.remaining_squares(), 15);
+    let extended =
+      PieceSet::with_preset(Team::Black, PieceSetPreset::Extended, 2);
+    assert_eq!(extended.remaining_squares(), 2 * SQUARES_PER_TEAM + 1);
+  }
+
+  #[test]
+  fn test_cathedral_set() {
+    let mut set = PieceSet::new(Team::None);
+    assert_eq!(set.take(PieceKind::Tavern), None);
+    assert_eq!(set.take(PieceKind::Cathedral), Some(Piece::new_cathedral()));
+    assert!(set.is_empty());
+    assert!(PieceSet::empty(Team::Black).is_empty());
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_return_foreign_piece() {
+    PieceSet::new(Team::White).return_piece(Piece::new_tavern(Team::Black));
+  }
+}
@@ -3,7 +3,8 @@ use std::{
   ops::{Add, Sub},
 };
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+/// Positions are ordered row by row: by `x` first, then by `y`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 pub struct Position {
   pub x: usize,
   pub y: usize,
@@ -139,6 +140,15 @@ mod tests {
 
   use super::*;
 
+  #[test]
+  fn test_ordering() {
+    let mut positions: Vec<Position> =
+      vec![(1, 0).into(), (0, 2).into(), (0, 1).into()];
+    positions.sort();
+    assert_eq!(positions, [(0, 1).into(), (0, 2).into(), (1, 0).into()]);
+    assert_eq!(positions.binary_search(&(0, 2).into()), Ok(1));
+  }
+
   #[test]
   fn test_manhattan_distance() {
     let p1 = Position { x: 1, y: 2 };
@@ -0,0 +1,276 @@
+//! Vector outlines of pieces and claimed territory.
+//!
+//! Polygons are built on the grid of tile corners: tile at position `(x, y)`
+//! spans corners `(x, y)` to `(x + 1, y + 1)`, so a board of size `n` has
+//! corners from `(0, 0)` to `(n, n)`.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+  board::{Board, Tile},
+  piece::PieceKind,
+  position::Position,
+  Team,
+};
+
+/// What a polygon outlines.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Shape {
+  Piece(PieceKind),
+  Territory,
+  /// Blocked tiles. Their polygons belong to `Team::None`.
+  Ruins,
+}
+
+/// Outline of a group of tiles.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Polygon {
+  pub team: Team,
+  pub shape: Shape,
+  /// Closed rings of corner coordinates, each listed once without repeating
+  /// the first corner at the end. The first ring is the outer boundary and
+  /// any other rings are holes. Rings only contain corners where the outline
+  /// turns.
+  pub rings: Vec<Vec<Position>>,
+}
+
+/// Returns outlines of every piece on the board followed by outlines of every
+/// connected region of claimed or blocked tiles.
+pub fn to_polygons(board: &Board) -> Vec<Polygon> {
+  let mut pieces = board.pieces().collect::<Vec<_>>();
+  pieces.sort_by_key(|piece| {
+    let p = piece.position();
+    (p.x, p.y)
+  });
+  let mut polygons = pieces
+    .into_iter()
+    .map(|piece| Polygon {
+      team: piece.team(),
+      shape: Shape::Piece(piece.kind()),
+      rings: outline(&piece.occupied_positions_iter().collect()),
+    })
+    .collect::<Vec<_>>();
+
+  let size = board.size();
+  let mut visited = HashSet::new();
+  for x in 0..size.x {
+    for y in 0..size.y {
+      let position = Position { x, y };
+      let tile = board.tile(position);
+      let (team, shape) = match tile {
+        Tile::Empty(team) if team != Team::None => (team, Shape::Territory),
+        Tile::Blocked => (Team::None, Shape::Ruins),
+        _ => continue,
+      };
+      if visited.contains(&position) {
+        continue;
+      }
+      let mut region = HashSet::new();
+      let mut stack = vec![position];
+      while let Some(p) = stack.pop() {
+        if !region.insert(p) {
+          continue;
+        }
+        stack.extend(
+          p.orthogonal_adjacent_positions_iter(size)
+            .filter(|p| board.tile(*p) == tile),
+        );
+      }
+      polygons.push(Polygon {
+        team,
+        shape,
+        rings: outline(&region),
+      });
+      visited.extend(region);
+    }
+  }
+  polygons
+}
+
+/// Returns closed rings that outline given set of tiles. Tiles touching only
+/// by corners are outlined separately. Rings go clockwise when `x` axis
+/// points down and `y` axis points right, so holes go counterclockwise.
+pub fn outline(tiles: &HashSet<Position>) -> Vec<Vec<Position>> {
+  let has = |x: usize, y: usize, dx: isize, dy: isize| match (
+    x.checked_add_signed(dx),
+    y.checked_add_signed(dy),
+  ) {
+    (Some(x), Some(y)) => tiles.contains(&Position { x, y }),
+    _ => false,
+  };
+
+  // Directed boundary edges with the outlined tiles on their right.
+  let mut edges = HashMap::<Position, Vec<Position>>::new();
+  for &Position { x, y } in tiles {
+    let corner = |x, y| Position { x, y };
+    let sides = [
+      ((-1, 0), corner(x, y), corner(x, y + 1)),
+      ((0, 1), corner(x, y + 1), corner(x + 1, y + 1)),
+      ((1, 0), corner(x + 1, y + 1), corner(x + 1, y)),
+      ((0, -1), corner(x + 1, y), corner(x, y)),
+    ];
+    for ((dx, dy), from, to) in sides {
+      if !has(x, y, dx, dy) {
+        edges.entry(from).or_default().push(to);
+      }
+    }
+  }
+
+  let mut rings = Vec::new();
+  while let Some(&start) = edges.keys().min_by_key(|p| (p.x, p.y)) {
+    let mut ring = vec![start];
+    let mut from = start;
+    let mut to = take_edge(&mut edges, from, None);
+    while to != start {
+      let next = take_edge(&mut edges, to, Some(direction(from, to)));
+      ring.push(to);
+      (from, to) = (to, next);
+    }
+    rings.push(without_collinear_corners(ring));
+  }
+  rings
+}
+
+/// Returns unit direction of the edge going from `from` to `to`.
+fn direction(from: Position, to: Position) -> (isize, isize) {
+  (
+    to.x as isize - from.x as isize,
+    to.y as isize - from.y as isize,
+  )
+}
+
+/// Removes and returns end of an edge starting at `from`. If there is a choice
+/// the edge turning right relative to `incoming` direction is preferred, so
+/// rings don't cross at corners shared diagonally.
+fn take_edge(
+  edges: &mut HashMap<Position, Vec<Position>>,
+  from: Position,
+  incoming: Option<(isize, isize)>,
+) -> Position {
+  let ends = edges.get_mut(&from).expect("outline must be closed");
+  let index = match incoming {
+    Some((dx, dy)) if ends.len() > 1 => ends
+      .iter()
+      .position(|to| direction(from, *to) == (dy, -dx))
+      .unwrap_or(0),
+    _ => 0,
+  };
+  let to = ends.swap_remove(index);
+  if ends.is_empty() {
+    edges.remove(&from);
+  }
+  to
+}
+
+/// Removes corners lying on a straight line between their neighbours. The
+/// first corner of `ring` must be a turning one.
+fn without_collinear_corners(ring: Vec<Position>) -> Vec<Position> {
+  let len = ring.len();
+  (0..len)
+    .filter(|&i| {
+      let prev = ring[(i + len - 1) % len];
+      let next = ring[(i + 1) % len];
+      direction(prev, ring[i]) != direction(ring[i], next)
+    })
+    .map(|i| ring[i])
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::piece::Piece;
+
+  fn corners(coords: &[(usize, usize)]) -> Vec<Position> {
+    coords.iter().copied().map(Position::from).collect()
+  }
+
+  #[test]
+  fn test_outline_pieces() {
+    let tavern = Piece::new_tavern(Team::White);
+    let tiles = tavern.occupied_positions_iter((2, 3).into()).collect();
+    assert_eq!(outline(&tiles), [corners(&[
+      (2, 3),
+      (2, 4),
+      (3, 4),
+      (3, 3)
+    ])]);
+
+    let bridge = Piece::new_bridge(Team::White);
+    let tiles = bridge.occupied_positions_iter((0, 0).into()).collect();
+    assert_eq!(outline(&tiles), [corners(&[
+      (0, 0),
+      (0, 1),
+      (3, 1),
+      (3, 0)
+    ])]);
+
+    let castle = Piece::new_castle(Team::White);
+    let tiles = castle.occupied_positions_iter((0, 0).into()).collect();
+    assert_eq!(outline(&tiles), [corners(&[
+      (0, 0),
+      (0, 3),
+      (2, 3),
+      (2, 2),
+      (1, 2),
+      (1, 1),
+      (2, 1),
+      (2, 0),
+    ])]);
+  }
+
+  #[test]
+  fn test_outline_diagonal_and_holes() {
+    let tiles = corners(&[(0, 0), (1, 1)]).into_iter().collect();
+    assert_eq!(outline(&tiles), [
+      corners(&[(0, 0), (0, 1), (1, 1), (1, 0)]),
+      corners(&[(1, 1), (1, 2), (2, 2), (2, 1)]),
+    ]);
+
+    let tiles = (0..3)
+      .flat_map(|x| (0..3).map(move |y| Position { x, y }))
+      .filter(|p| *p != Position { x: 1, y: 1 })
+      .collect();
+    assert_eq!(outline(&tiles), [
+      corners(&[(0, 0), (0, 3), (3, 3), (3, 0)]),
+      corners(&[(1, 1), (2, 1), (2, 2), (1, 2)]),
+    ]);
+  }
+
+  #[test]
+  fn test_to_polygons() {
+    let mut board = Board::with_size(5);
+    board.place_piece(Piece::new_stable(Team::Black), (0, 0).into());
+    board.place_piece(Piece::new_cathedral(), (1, 2).into());
+    board.set_tiles_for_test(&[(4, 0), (4, 1)], Tile::Empty(Team::Black));
+    board.set_tiles_for_test(&[(4, 4)], Tile::Empty(Team::White));
+    board.set_tiles_for_test(&[(0, 4), (1, 4)], Tile::Blocked);
+
+    let polygons = to_polygons(&board);
+    assert_eq!(polygons.len(), 5);
+    assert_eq!(polygons[2], Polygon {
+      team: Team::None,
+      shape: Shape::Ruins,
+      rings: vec![corners(&[(0, 4), (0, 5), (2, 5), (2, 4)])],
+    });
+    let polygons = [&polygons[..2], &polygons[3..]].concat();
+    assert_eq!(polygons[0], Polygon {
+      team: Team::Black,
+      shape: Shape::Piece(PieceKind::Stable),
+      rings: vec![corners(&[(0, 0), (0, 1), (2, 1), (2, 0)])],
+    });
+    assert_eq!(polygons[1].team, Team::None);
+    assert_eq!(polygons[1].shape, Shape::Piece(PieceKind::Cathedral));
+    assert_eq!(polygons[1].rings[0].len(), 12);
+    assert_eq!(polygons[2], Polygon {
+      team: Team::Black,
+      shape: Shape::Territory,
+      rings: vec![corners(&[(4, 0), (4, 2), (5, 2), (5, 0)])],
+    });
+    assert_eq!(polygons[3], Polygon {
+      team: Team::White,
+      shape: Shape::Territory,
+      rings: vec![corners(&[(4, 4), (4, 5), (5, 5), (5, 4)])],
+    });
+  }
+}
@@ -0,0 +1,42 @@
+/// Small deterministic pseudo-random number generator (SplitMix64). The same
+/// seed yields the same sequence on every platform and crate version, which
+/// is all seeded variants need; it is not suitable for cryptography.
+#[derive(Clone, Debug)]
+pub(crate) struct SplitMix64 {
+  state: u64,
+}
+
+impl SplitMix64 {
+  pub(crate) fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+
+  pub(crate) fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  /// Returns a number in range `0..bound`. Panics if `bound` is zero.
+  pub(crate) fn below(&mut self, bound: usize) -> usize {
+    (self.next_u64() % bound as u64) as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sequence_is_stable() {
+    let mut rng = SplitMix64::new(0);
+    assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+    assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+    let mut a = SplitMix64::new(42);
+    let mut b = SplitMix64::new(42);
+    assert!((0..100).all(|_| a.below(10) == b.below(10)));
+  }
+}
@@ -0,0 +1,115 @@
+use crate::consts::STANDARD_BOARD_SIZE;
+
+/// Adjacency used to join empty and capturable tiles into regions.
+///
+/// Community implementations disagree on whether a region may leak through a
+/// diagonal gap between two buildings, so both semantics are provided.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Connectivity {
+  /// Tiles are connected through their sides only. Buildings that touch each
+  /// other by corners form a closed wall. This is how the official rules
+  /// treat enclosures.
+  #[default]
+  Orthogonal,
+  /// Tiles are connected through their sides and corners. A region leaks
+  /// through any diagonal gap, so walls have to be connected by sides.
+  Diagonal,
+}
+
+/// Set of rule options a board plays by. `Rules::default()` follows the
+/// official rulebook.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rules {
+  /// How tiles are joined into regions when looking for enclosures.
+  pub region_connectivity: Connectivity,
+  /// Whether the cathedral is captured like an enemy piece. If not, regions
+  /// holding the cathedral are never captured.
+  pub cathedral_capturable: bool,
+  /// Whether a team is forbidden to place pieces on tiles the other team
+  /// has claimed.
+  pub claimed_ground_blocks: bool,
+  /// Whether enclosed regions holding a single foreign piece are captured.
+  /// If not, only empty regions get claimed.
+  pub capture_occupied_regions: bool,
+  /// How teams' standings are compared when the game ends.
+  pub scoring: Scoring,
+}
+
+impl Default for Rules {
+  fn default() -> Self {
+    Self {
+      region_connectivity: Connectivity::default(),
+      cathedral_capturable: true,
+      claimed_ground_blocks: true,
+      capture_occupied_regions: true,
+      scoring: Scoring::default(),
+    }
+  }
+}
+
+/// What a team's penalty is made of. The team with the lower penalty wins.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Scoring {
+  /// Only squares of pieces left in hand count, as in the official rules.
+  #[default]
+  Unplaced,
+  /// Experimental mode of community variant leagues: every claimed tile of
+  /// territory takes `weight` squares off the team's penalty, so it can go
+  /// below zero.
+  WeightedTerritory { weight: usize },
+}
+
+/// How a game with equal penalties is decided.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TieBreak {
+  /// Both teams share the win, the game is a draw.
+  #[default]
+  Shared,
+  /// The team that claimed more territory wins. Equal territory is a draw.
+  Territory,
+  /// The team that ran out of placements and passed first wins. If neither
+  /// team passed, the game is a draw.
+  FirstToFinish,
+}
+
+/// Which pieces each team starts with. Sets are sized to fill boards of
+/// certain size about as tightly as the standard set fills the standard
+/// board.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum PieceSetPreset {
+  /// The 14 pieces of the board game, for a 10×10 board. With three or four
+  /// teams each team gets one piece of every kind instead.
+  #[default]
+  Standard,
+  /// Six small pieces covering 15 squares, for a 6×6 board.
+  Mini,
+  /// The standard set twice and one more tavern, 95 squares in total, for
+  /// a 14×14 board.
+  Extended,
+}
+
+/// Options a game plays by: board rules and what rulebook decides the rest.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GameConfig {
+  pub rules: Rules,
+  pub tie_break: TieBreak,
+  /// Number of tiles on each side of the board.
+  pub board_size: usize,
+  /// Number of teams taking turns, from two to four. Teams join in order
+  /// of `Team::PLAYERS`.
+  pub players: usize,
+  /// Pieces each team starts with.
+  pub piece_set: PieceSetPreset,
+}
+
+impl Default for GameConfig {
+  fn default() -> Self {
+    Self {
+      rules: Rules::default(),
+      tie_break: TieBreak::default(),
+      board_size: STANDARD_BOARD_SIZE,
+      players: 2,
+      piece_set: PieceSetPreset::default(),
+    }
+  }
+}
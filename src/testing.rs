@@ -0,0 +1,348 @@
+//! Helpers for building boards and checking rules in tests.
+
+use std::collections::HashSet;
+
+use crate::{
+  board::{Board, Tile},
+  error::ParseBoardError,
+  game::Move,
+  piece::{Piece, PieceKind},
+  position::Position,
+  rules::Rules,
+  Team,
+};
+
+/// Builds a board from ASCII art, one line per row of tiles and one
+/// whitespace-separated token per tile:
+///
+///   - `.` is a neutral empty tile;
+///   - `w`, `b`, `r` or `g` is an empty tile claimed by white, black, red
+///     or green team;
+///   - `X` is a blocked tile;
+///   - `W`, `B`, `R`, `G` or `C` is a tile occupied by a piece of one of the
+///     teams or the cathedral. Orthogonally adjacent equal tokens make up a
+///     single piece, so touching pieces of the same team need distinct digit
+///     suffixes, like `W1` and `W2`.
+///
+/// Pieces are placed in order their first tiles appear in the art. Every
+/// piece must match the layout of some piece of its team, so the board is
+/// always one that could come up in a game, save for captures.
+pub struct BoardBuilder<'a> {
+  art: &'a str,
+  rules: Rules,
+}
+
+impl<'a> BoardBuilder<'a> {
+  pub fn new(art: &'a str) -> Self {
+    Self {
+      art,
+      rules: Rules::default(),
+    }
+  }
+
+  /// Sets rules of the built board.
+  pub fn rules(mut self, rules: Rules) -> Self {
+    self.rules = rules;
+    self
+  }
+
+  /// Returns the board drawn by the art. Pieces that don't match any
+  /// layout are reported with their index in placement order.
+  pub fn build(self) -> Result<Board, ParseBoardError> {
+    let rows = self
+      .art
+      .lines()
+      .map(|line| line.split_whitespace().collect::<Vec<_>>())
+      .filter(|row| !row.is_empty())
+      .collect::<Vec<_>>();
+    let size = rows.len();
+    if rows.iter().any(|row| row.len() != size) {
+      return Err(ParseBoardError::NotSquare);
+    }
+
+    let mut board = Board::with_size_and_rules(size, self.rules);
+    let mut visited = HashSet::<Position>::new();
+    let mut piece_count = 0;
+    for (x, row) in rows.iter().enumerate() {
+      for (y, token) in row.iter().enumerate() {
+        let position = Position { x, y };
+        let tile = match *token {
+          "." => continue,
+          "w" => Tile::Empty(Team::White),
+          "b" => Tile::Empty(Team::Black),
+          "r" => Tile::Empty(Team::Red),
+          "g" => Tile::Empty(Team::Green),
+          "X" => Tile::Blocked,
+          _ => {
+            let team = piece_team(token).ok_or_else(|| {
+              ParseBoardError::InvalidTile(token.to_string(), position)
+            })?;
+            if visited.contains(&position) {
+              continue;
+            }
+            let positions = piece_positions(&rows, position);
+            visited.extend(positions.iter().copied());
+            board
+              .insert_piece_covering(team, &positions)
+              .ok_or(ParseBoardError::InvalidPiece(piece_count))?;
+            piece_count += 1;
+            continue;
+          }
+        };
+        board.set_tile(position, tile);
+      }
+    }
+    Ok(board)
+  }
+}
+
+/// Checks outcome of a single placement, written as given-when-then: given
+/// a board drawn as art (see [`BoardBuilder`]), when a team makes a
+/// placement written in notation of [`Move`], like `W:TAVERN@a2^U`, then
+/// given pieces are captured, tiles are claimed and the board looks like
+/// another art.
+///
+/// Expectations that aren't set are not checked. Panics on invalid art or
+/// placement and on any unmet expectation.
+pub struct Scenario<'a> {
+  board: BoardBuilder<'a>,
+  placement: Option<&'a str>,
+  captured: Option<Vec<PieceKind>>,
+  claimed: Option<HashSet<Position>>,
+  tiles: Option<&'a str>,
+}
+
+impl<'a> Scenario<'a> {
+  /// Starts a scenario on the board drawn by `art`.
+  pub fn given(art: &'a str) -> Self {
+    Self {
+      board: BoardBuilder::new(art),
+      placement: None,
+      captured: None,
+      claimed: None,
+      tiles: None,
+    }
+  }
+
+  /// Sets rules of the board.
+  pub fn rules(mut self, rules: Rules) -> Self {
+    self.board = self.board.rules(rules);
+    self
+  }
+
+  /// Sets placement to make, like `W:TAVERN@a2^U`. The cathedral is placed
+  /// with `PieceKind::Cathedral` regardless of the team.
+  pub fn when(mut self, placement: &'a str) -> Self {
+    self.placement = Some(placement);
+    self
+  }
+
+  /// Expects kinds of pieces captured by the placement, in order they were
+  /// placed on the board.
+  pub fn then_captured(mut self, kinds: &[PieceKind]) -> Self {
+    self.captured = Some(kinds.to_vec());
+    self
+  }
+
+  /// Expects exactly these tiles to be claimed by the placement.
+  pub fn then_claimed(mut self, positions: &[(usize, usize)]) -> Self {
+    self.claimed = Some(positions.iter().copied().map(Into::into).collect());
+    self
+  }
+
+  /// Expects board tiles to look like `art` after the placement.
+  pub fn then_tiles(mut self, art: &'a str) -> Self {
+    self.tiles = Some(art);
+    self
+  }
+
+  /// Makes the placement, checks expectations and returns the board for
+  /// further checks.
+  #[track_caller]
+  pub fn run(self) -> Board {
+    let mut board = self.board.build().expect("scenario art must be valid");
+    let placement = self.placement.expect("scenario must have a placement");
+    let Ok(Move::Place {
+      team,
+      kind,
+      rotation,
+      position,
+    }) = placement.parse::<Move>()
+    else {
+      panic!("{placement:?} is not a placement");
+    };
+    let mut piece = Piece::new(kind, team);
+    piece.rotate_to(rotation);
+    let outcome = board
+      .try_place_piece(piece, position)
+      .unwrap_or_else(|e| panic!("{placement} must be legal: {e}"));
+
+    if let Err(e) = board.validate() {
+      panic!("board after {placement} must be valid: {e}");
+    }
+
+    if let Some(captured) = self.captured {
+      let kinds = outcome
+        .captured_pieces
+        .iter()
+        .map(Piece::kind)
+        .collect::<Vec<_>>();
+      assert_eq!(kinds, captured, "captured pieces don't match");
+      assert_eq!(
+        outcome.cathedral_captured,
+        captured.contains(&PieceKind::Cathedral),
+        "cathedral capture doesn't match"
+      );
+    }
+    if let Some(claimed) = self.claimed {
+      assert_eq!(outcome.claimed_tiles, claimed, "claimed tiles don't match");
+    }
+    if let Some(art) = self.tiles {
+      let expected = BoardBuilder::new(art)
+        .build()
+        .expect("expected art must be valid");
+      assert_eq!(expected.size(), board.size(), "board sizes don't match");
+      let size = board.size();
+      for x in 0..size.x {
+        for y in 0..size.y {
+          let position = Position { x, y };
+          assert_eq!(
+            board.tile(position),
+            expected.tile(position),
+            "tiles at {position} don't match"
+          );
+        }
+      }
+    }
+    board
+  }
+}
+
+/// Returns team of a piece token: a team letter optionally followed by
+/// digits.
+fn piece_team(token: &str) -> Option<Team> {
+  let team = match token.get(..1)? {
+    "W" => Team::White,
+    "B" => Team::Black,
+    "R" => Team::Red,
+    "G" => Team::Green,
+    "C" => Team::None,
+    _ => return None,
+  };
+  token[1..]
+    .bytes()
+    .all(|b| b.is_ascii_digit())
+    .then_some(team)
+}
+
+/// Returns positions reachable from `start` through orthogonally adjacent
+/// tokens equal to the one at `start`.
+fn piece_positions(rows: &[Vec<&str>], start: Position) -> Vec<Position> {
+  let token = rows[start.x][start.y];
+  let mut positions = vec![start];
+  let mut queue = vec![start];
+  while let Some(p) = queue.pop() {
+    let neighbours = [
+      p.x.checked_sub(1).map(|x| Position { x, y: p.y }),
+      Some(Position { x: p.x + 1, y: p.y }),
+      p.y.checked_sub(1).map(|y| Position { x: p.x, y }),
+      Some(Position { x: p.x, y: p.y + 1 }),
+    ];
+    for n in neighbours.into_iter().flatten() {
+      let same = rows.get(n.x).and_then(|row| row.get(n.y)) == Some(&token);
+      if same && !positions.contains(&n) {
+        positions.push(n);
+        queue.push(n);
+      }
+    }
+  }
+  positions
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::piece::{Piece, PieceKind};
+
+  #[test]
+  fn test_board_builder() -> Result<(), ParseBoardError> {
+    let board = BoardBuilder::new(
+      "
+      W1 W1 W2 .
+      .  B  B  B
+      .  .  B2 .
+      w  b  .  X
+      ",
+    )
+    .build()?;
+    assert_eq!(board.size(), (4, 4).into());
+    assert_eq!(
+      board
+        .pieces_in_placement_order()
+        .map(|p| (p.kind(), p.team(), p.position()))
+        .collect::<Vec<_>>(),
+      [
+        (PieceKind::Stable, Team::White, (0, 0).into()),
+        (PieceKind::Tavern, Team::White, (0, 2).into()),
+        (PieceKind::Bridge, Team::Black, (1, 1).into()),
+        (PieceKind::Tavern, Team::Black, (2, 2).into()),
+      ]
+    );
+    assert_eq!(board.tile((3, 0).into()), Tile::Empty(Team::White));
+    assert_eq!(board.tile((3, 1).into()), Tile::Empty(Team::Black));
+    assert_eq!(board.tile((3, 2).into()), Tile::Empty(Team::None));
+    assert_eq!(board.tile((3, 3).into()), Tile::Blocked);
+    Ok(())
+  }
+
+  #[test]
+  fn test_board_builder_cathedral() -> Result<(), ParseBoardError> {
+    let board = BoardBuilder::new(
+      "
+      . C . .
+      C C C .
+      . C . .
+      . C . .
+      ",
+    )
+    .build()?;
+    assert_eq!(
+      board.pieces().next().map(Piece::kind),
+      Some(PieceKind::Cathedral)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_board_builder_errors() {
+    let build = |art| BoardBuilder::new(art).build().err();
+    assert_eq!(build(". .\n. .\n."), Some(ParseBoardError::NotSquare));
+    assert_eq!(
+      build(". .\n. Y"),
+      Some(ParseBoardError::InvalidTile("Y".into(), (1, 1).into()))
+    );
+    assert_eq!(
+      build(". .\n. Wx"),
+      Some(ParseBoardError::InvalidTile("Wx".into(), (1, 1).into()))
+    );
+    assert_eq!(
+      build("W . .\n. . .\n. . C"),
+      Some(ParseBoardError::InvalidPiece(1))
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "captured pieces don't match")]
+  fn test_scenario_unmet_expectation() {
+    Scenario::given(
+      "
+      B W .
+      . . .
+      . . .
+      ",
+    )
+    .when("W:TAVERN@a2^U")
+    .then_captured(&[])
+    .run();
+  }
+}
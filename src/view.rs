@@ -0,0 +1,138 @@
+//! Experimental fog-of-war variant, where each player only sees the part of
+//! the board around their own buildings and territory.
+
+use std::fmt::Display;
+
+use ndarray::Array2;
+
+use crate::{
+  board::{Board, Tile},
+  position::Position,
+  Team,
+};
+
+/// Board as seen by a single team. Servers running the fog-of-war variant
+/// should send clients this view instead of the board itself.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GameView {
+  team: Team,
+  tiles: Array2<Option<Tile>>,
+}
+
+impl GameView {
+  /// Returns view of `board` for given `team`. A team sees tiles its pieces
+  /// occupy, tiles it claimed and every tile adjacent to those, including
+  /// diagonally. When a region gets claimed, all of it is revealed to its new
+  /// owner. `Team::None` is a spectator and sees the whole board.
+  pub fn fogged_for(board: &Board, team: Team) -> Self {
+    let size = board.size();
+    let mut tiles = Array2::from_elem((size.x, size.y), None);
+    for x in 0..size.x {
+      for y in 0..size.y {
+        let position = Position { x, y };
+        let tile = board.tile(position);
+        let owned =
+          matches!(tile, Tile::Empty(t) | Tile::Occupied(t) if t == team);
+        if team == Team::None {
+          tiles[(x, y)] = Some(tile);
+        } else if owned {
+          tiles[(x, y)] = Some(tile);
+          for p in position.diagonal_adjacent_positions_iter(size) {
+            tiles[(p.x, p.y)] = Some(board.tile(p));
+          }
+        }
+      }
+    }
+    Self { team, tiles }
+  }
+
+  /// Returns team this view was made for.
+  pub fn team(&self) -> Team {
+    self.team
+  }
+
+  /// Returns size of the viewed board as position.
+  pub fn size(&self) -> Position {
+    Position::from(self.tiles.dim())
+  }
+
+  /// Returns tile at given `position` or `None` if it is hidden or out of
+  /// bounds.
+  pub fn tile(&self, position: Position) -> Option<Tile> {
+    self.tiles.get((position.x, position.y)).copied().flatten()
+  }
+
+  /// Returns `true` if tile at given `position` is visible.
+  pub fn is_visible(&self, position: Position) -> bool {
+    self.tile(position).is_some()
+  }
+}
+
+impl Display for GameView {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    self.tiles.rows().into_iter().try_for_each(|row| {
+      row.iter().try_for_each(|tile| match tile {
+        Some(tile) => write!(f, "{tile}"),
+        None => write!(f, "··"),
+      })?;
+      writeln!(f)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::piece::Piece;
+
+  #[test]
+  fn test_fogged_for() {
+    let mut board = Board::default();
+    board.place_piece(Piece::new_tavern(Team::White), (0, 0).into());
+    board.place_piece(Piece::new_tavern(Team::Black), (5, 5).into());
+    board.place_piece(Piece::new_tavern(Team::Black), (1, 1).into());
+
+    let view = GameView::fogged_for(&board, Team::White);
+    assert_eq!(view.team(), Team::White);
+    assert_eq!(view.tile((0, 0).into()), Some(Tile::Occupied(Team::White)));
+    assert_eq!(view.tile((1, 1).into()), Some(Tile::Occupied(Team::Black)));
+    assert_eq!(view.tile((0, 1).into()), Some(Tile::Empty(Team::None)));
+    assert_eq!(view.tile((2, 2).into()), None);
+    assert_eq!(view.tile((5, 5).into()), None);
+    assert_eq!(view.tile((10, 10).into()), None);
+    assert_eq!(
+      (0..10)
+        .flat_map(|x| (0..10).map(move |y| (x, y)))
+        .filter(|p| view.is_visible((*p).into()))
+        .count(),
+      4
+    );
+
+    let view = GameView::fogged_for(&board, Team::Black);
+    assert!(view.is_visible((0, 0).into()));
+    assert!(view.is_visible((4, 6).into()));
+    assert!(!view.is_visible((7, 7).into()));
+
+    let view = GameView::fogged_for(&board, Team::None);
+    assert!((0..10).all(|x| (0..10).all(|y| view.is_visible((x, y).into()))));
+  }
+
+  #[test]
+  fn test_claims_reveal_region() {
+    let mut board = Board::default();
+    board.place_piece(Piece::new_bridge(Team::White), (0, 3).into());
+    let mut stable = Piece::new_stable(Team::White);
+    stable.rotate_clockwise();
+    board.place_piece(stable.clone(), (3, 0).into());
+    board.place_piece(stable, (3, 2).into());
+
+    let view = GameView::fogged_for(&board, Team::White);
+    assert_eq!(view.tile((0, 0).into()), Some(Tile::Empty(Team::White)));
+    assert_eq!(view.tile((1, 1).into()), Some(Tile::Empty(Team::White)));
+    assert!(view.is_visible((4, 4).into()));
+    assert!(!view.is_visible((5, 5).into()));
+
+    let view = GameView::fogged_for(&board, Team::Black);
+    assert!(!view.is_visible((0, 0).into()));
+  }
+}